@@ -0,0 +1,66 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use neural_network_ml::gemm::{Operand, gemm, gemm_naive};
+
+// (m, n, k) shapes met when training on MNIST with a 784-128-10 network and batches of 32
+const SHAPES: [(&str, usize, usize, usize); 5] = [
+    ("hidden_forward", 128, 32, 784),
+    ("output_forward", 10, 32, 128),
+    ("hidden_weight_gradient", 128, 784, 32),
+    ("full_batch_forward", 30, 1000, 784),
+    ("square_512", 512, 512, 512),
+];
+
+fn random_data(len: usize, rng: &mut ChaCha8Rng) -> Vec<f32> {
+    (0..len).map(|_| rng.random_range(-1.0..1.0)).collect()
+}
+
+fn matmul(c: &mut Criterion) {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+    for (name, m, n, k) in SHAPES {
+        let a = random_data(m * k, &mut rng);
+        let b = random_data(k * n, &mut rng);
+        let mut product = vec![0.0; m * n];
+        let mut group = c.benchmark_group(name);
+
+        group.bench_function(
+            BenchmarkId::new("blocked", format!("{m}x{k}x{n}")),
+            |bencher| {
+                bencher.iter(|| {
+                    gemm(
+                        m,
+                        n,
+                        k,
+                        Operand::row_major(black_box(&a), k),
+                        Operand::row_major(black_box(&b), n),
+                        &mut product,
+                    )
+                })
+            },
+        );
+        group.bench_function(
+            BenchmarkId::new("naive", format!("{m}x{k}x{n}")),
+            |bencher| {
+                bencher.iter(|| {
+                    gemm_naive(
+                        m,
+                        n,
+                        k,
+                        Operand::row_major(black_box(&a), k),
+                        Operand::row_major(black_box(&b), n),
+                        &mut product,
+                    )
+                })
+            },
+        );
+        group.finish();
+    }
+}
+
+criterion_group!(benches, matmul);
+criterion_main!(benches);
@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{element::Element, matrix::Matrix};
+
+const SELU_LAMBDA: f64 = 1.050_700_987_355_480_5;
+const SELU_ALPHA: f64 = 1.673_263_242_354_377_3;
+// sqrt(2 / pi) and the cubic term of the tanh approximation of GELU
+const GELU_COEFFICIENT: f64 = 0.797_884_560_802_865_4;
+const GELU_CUBIC: f64 = 0.044_715;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum ActivationFunction {
+    Identity,
+    Sigmoid,
+    Tanh,
+    Relu,
+    LeakyRelu { slope: f32 },
+    Elu { alpha: f32 },
+    Selu,
+    Gelu,
+    Swish,
+    Softplus,
+    // Applied to each column as a whole, its derivative is a Jacobian rather than elementwise
+    Softmax,
+}
+
+impl ActivationFunction {
+    // Elementwise activation, `activate_into` handles softmax before getting here
+    fn function<T: Element>(&self, x: T) -> T {
+        let zero = T::zero();
+        let one = T::one();
+
+        match self {
+            ActivationFunction::Identity => x,
+            ActivationFunction::Sigmoid => sigmoid(x),
+            ActivationFunction::Tanh => x.tanh(),
+            ActivationFunction::Relu => x.max(zero),
+            ActivationFunction::LeakyRelu { slope } => {
+                if x > zero {
+                    x
+                } else {
+                    T::from_f32(*slope) * x
+                }
+            }
+            ActivationFunction::Elu { alpha } => {
+                if x > zero {
+                    x
+                } else {
+                    T::from_f32(*alpha) * x.exp_m1()
+                }
+            }
+            ActivationFunction::Selu => {
+                let lambda = T::from_f64(SELU_LAMBDA);
+                if x > zero {
+                    lambda * x
+                } else {
+                    lambda * T::from_f64(SELU_ALPHA) * x.exp_m1()
+                }
+            }
+            ActivationFunction::Gelu => {
+                let inner =
+                    T::from_f64(GELU_COEFFICIENT) * (x + T::from_f64(GELU_CUBIC) * x.powi(3));
+                T::from_f64(0.5) * x * (one + inner.tanh())
+            }
+            ActivationFunction::Swish => x * sigmoid(x),
+            // Rearranged so large inputs don't overflow exp
+            ActivationFunction::Softplus => x.max(zero) + (-x.abs()).exp().ln_1p(),
+            ActivationFunction::Softmax => unreachable!("Softmax is not elementwise"),
+        }
+    }
+
+    // Elementwise derivative, `backward_into` handles softmax before getting here
+    fn derivative<T: Element>(&self, x: T) -> T {
+        let zero = T::zero();
+        let one = T::one();
+
+        match self {
+            ActivationFunction::Identity => one,
+            ActivationFunction::Sigmoid => sigmoid(x) * (one - sigmoid(x)),
+            ActivationFunction::Tanh => one - x.tanh().powi(2),
+            ActivationFunction::Relu => {
+                if x > zero {
+                    one
+                } else {
+                    zero
+                }
+            }
+            ActivationFunction::LeakyRelu { slope } => {
+                if x > zero {
+                    one
+                } else {
+                    T::from_f32(*slope)
+                }
+            }
+            ActivationFunction::Elu { alpha } => {
+                if x > zero {
+                    one
+                } else {
+                    T::from_f32(*alpha) * x.exp()
+                }
+            }
+            ActivationFunction::Selu => {
+                let lambda = T::from_f64(SELU_LAMBDA);
+                if x > zero {
+                    lambda
+                } else {
+                    lambda * T::from_f64(SELU_ALPHA) * x.exp()
+                }
+            }
+            ActivationFunction::Gelu => {
+                let coefficient = T::from_f64(GELU_COEFFICIENT);
+                let cubic = T::from_f64(GELU_CUBIC);
+                let half = T::from_f64(0.5);
+
+                let tanh = (coefficient * (x + cubic * x.powi(3))).tanh();
+                let inner_derivative = coefficient * (one + T::from_f64(3.0) * cubic * x.powi(2));
+                half * (one + tanh) + half * x * (one - tanh.powi(2)) * inner_derivative
+            }
+            ActivationFunction::Swish => {
+                let s = sigmoid(x);
+                s + x * s * (one - s)
+            }
+            ActivationFunction::Softplus => sigmoid(x),
+            ActivationFunction::Softmax => unreachable!("Softmax is not elementwise"),
+        }
+    }
+
+    // Applies the activation to pre-activations `z`, each column is one sample
+    pub fn activate<T: Element>(&self, z: &Matrix<T>) -> Matrix<T> {
+        let mut a = Matrix::zeros(0, 0);
+        self.activate_into(z, &mut a);
+        a
+    }
+
+    // Writes `activate(z)` into `a`, reusing its allocation
+    pub fn activate_into<T: Element>(&self, z: &Matrix<T>, a: &mut Matrix<T>) {
+        a.copy_from(z);
+
+        match self {
+            ActivationFunction::Softmax => softmax_inplace(a),
+            _ => a.map_inplace(|x| self.function(x)),
+        }
+    }
+
+    // Turns the gradient with respect to the activations `a = activate(z)` into the gradient
+    // with respect to `z`
+    pub fn backward<T: Element>(
+        &self,
+        z: &Matrix<T>,
+        a: &Matrix<T>,
+        upstream: &Matrix<T>,
+    ) -> Matrix<T> {
+        let mut gradient = Matrix::zeros(0, 0);
+        self.backward_into(z, a, upstream, &mut gradient);
+        gradient
+    }
+
+    pub fn backward_into<T: Element>(
+        &self,
+        z: &Matrix<T>,
+        a: &Matrix<T>,
+        upstream: &Matrix<T>,
+        gradient: &mut Matrix<T>,
+    ) {
+        assert!(
+            upstream.rows == z.rows && upstream.cols == z.cols,
+            "Attempt to backpropagate gradient of size {}x{} through activations of size {}x{}",
+            upstream.rows,
+            upstream.cols,
+            z.rows,
+            z.cols
+        );
+        gradient.reset(a.rows, a.cols);
+
+        match self {
+            ActivationFunction::Softmax => {
+                // Jacobian-vector product per column, dz_i = a_i * (u_i - sum_j a_j * u_j)
+                for j in 0..a.cols {
+                    let weighted_sum: T = (0..a.rows)
+                        .map(|i| a.data[i * a.cols + j] * upstream.data[i * a.cols + j])
+                        .sum();
+
+                    for i in 0..a.rows {
+                        gradient.data[i * a.cols + j] =
+                            a.data[i * a.cols + j] * (upstream.data[i * a.cols + j] - weighted_sum);
+                    }
+                }
+            }
+            _ => {
+                for ((gradient, z), upstream) in
+                    gradient.data.iter_mut().zip(&z.data).zip(&upstream.data)
+                {
+                    *gradient = *upstream * self.derivative(*z);
+                }
+            }
+        }
+    }
+}
+
+fn sigmoid<T: Element>(x: T) -> T {
+    T::one() / (T::one() + (-x).exp())
+}
+
+// Column-wise softmax, shifted by the column maximum so large inputs don't overflow
+fn softmax_inplace<T: Element>(z: &mut Matrix<T>) {
+    for j in 0..z.cols {
+        let max = (0..z.rows)
+            .map(|i| z.data[i * z.cols + j])
+            .fold(T::neg_infinity(), T::max);
+
+        let mut sum = T::zero();
+        for i in 0..z.rows {
+            let exp = (z.data[i * z.cols + j] - max).exp();
+            z.data[i * z.cols + j] = exp;
+            sum += exp;
+        }
+
+        for i in 0..z.rows {
+            z.data[i * z.cols + j] /= sum;
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{
+    activation::ActivationFunction, element::Element, initializer::Initializer, loss::Loss,
+    network::Network, optimizer::OptimizerKind,
+};
+
+pub struct NetworkBuilder<T = f32> {
+    input_size: usize,
+    layers: Vec<(usize, ActivationFunction, Initializer)>,
+    bias_initializer: Initializer,
+    loss: Loss,
+    learning_rate: f32,
+    optimizer: OptimizerKind<T>,
+    seed: Option<u64>,
+}
+
+impl<T: Element> NetworkBuilder<T> {
+    pub fn new(input_size: usize) -> Self {
+        NetworkBuilder {
+            input_size,
+            layers: Vec::new(),
+            bias_initializer: Initializer::Zeros,
+            loss: Loss::MeanSquaredError,
+            learning_rate: 0.1,
+            optimizer: OptimizerKind::default(),
+            seed: None,
+        }
+    }
+
+    pub fn layer(self, size: usize, activation: ActivationFunction) -> Self {
+        self.layer_with_initializer(size, activation, Initializer::default_for(activation))
+    }
+
+    pub fn layer_with_initializer(
+        mut self,
+        size: usize,
+        activation: ActivationFunction,
+        initializer: Initializer,
+    ) -> Self {
+        self.layers.push((size, activation, initializer));
+        self
+    }
+
+    pub fn layers(mut self, layers: impl IntoIterator<Item = (usize, ActivationFunction)>) -> Self {
+        for (size, activation) in layers {
+            self = self.layer(size, activation);
+        }
+        self
+    }
+
+    pub fn bias_initializer(mut self, initializer: Initializer) -> Self {
+        self.bias_initializer = initializer;
+        self
+    }
+
+    pub fn loss(mut self, loss: Loss) -> Self {
+        self.loss = loss;
+        self
+    }
+
+    pub fn learning_rate(mut self, learning_rate: f32) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    pub fn optimizer(mut self, optimizer: OptimizerKind<T>) -> Self {
+        self.optimizer = optimizer;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(self) -> Network<T> {
+        Network::with_initializers(
+            self.input_size,
+            self.layers,
+            self.bias_initializer,
+            self.loss,
+            self.learning_rate,
+            self.optimizer,
+            self.seed,
+        )
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use crate::{element::Element, network::Network, training::EpochStats};
+
+// Returned by hooks to carry on training or end it early. Stopping from `on_epoch_start` or
+// `on_batch_end` skips the rest of the epoch, leaving it out of the returned stats
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    Stop,
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchStats {
+    pub epoch: u16,
+    // Index of the mini batch within the epoch
+    pub batch: usize,
+    // Mini batches trained on since training started, counting those before a resume
+    pub step: u64,
+    pub samples: usize,
+    // Average loss of the mini batch before the update
+    pub loss: f32,
+    pub learning_rate: f32,
+}
+
+// Hooks into the training loop, every hook does nothing by default
+pub trait Callback<T: Element = f32> {
+    fn on_epoch_start(&mut self, _epoch: u16, _epochs: u16) -> Control {
+        Control::Continue
+    }
+
+    fn on_batch_end(&mut self, _network: &Network<T>, _batch: &BatchStats) -> Control {
+        Control::Continue
+    }
+
+    fn on_epoch_end(&mut self, _network: &Network<T>, _stats: &EpochStats) -> Control {
+        Control::Continue
+    }
+
+    fn on_train_end(&mut self, _network: &Network<T>, _stats: &[EpochStats]) {}
+}
+
+// Runs `hook` on every callback, stopping if any of them asks to
+pub(crate) fn dispatch<T: Element>(
+    callbacks: &mut [&mut dyn Callback<T>],
+    mut hook: impl FnMut(&mut dyn Callback<T>) -> Control,
+) -> Control {
+    let mut control = Control::Continue;
+
+    for callback in callbacks.iter_mut() {
+        if hook(&mut **callback) == Control::Stop {
+            control = Control::Stop;
+        }
+    }
+
+    control
+}
+
+// Prints the progress of every epoch, or every 100th one for runs over 100 epochs
+#[derive(Default)]
+pub struct ProgressLogger {
+    epochs: u16,
+    durations: Vec<Duration>,
+}
+
+impl ProgressLogger {
+    fn logs(&self, epoch: u16) -> bool {
+        self.epochs <= 100 || epoch.is_multiple_of(100)
+    }
+}
+
+impl<T: Element> Callback<T> for ProgressLogger {
+    fn on_epoch_start(&mut self, epoch: u16, epochs: u16) -> Control {
+        self.epochs = epochs;
+        if self.logs(epoch) {
+            println!("Epoch {epoch} of {epochs}");
+        }
+
+        Control::Continue
+    }
+
+    fn on_epoch_end(&mut self, _network: &Network<T>, stats: &EpochStats) -> Control {
+        self.durations.push(stats.duration);
+
+        if self.logs(stats.epoch) {
+            print!(
+                "Epoch {} took {}s, average loss {}, accuracy {}",
+                stats.epoch,
+                stats.duration.as_millis() as f64 / 1000.0,
+                stats.loss,
+                stats.accuracy
+            );
+            if let (Some(loss), Some(accuracy)) = (stats.validation_loss, stats.validation_accuracy)
+            {
+                print!(", validation loss {loss}, validation accuracy {accuracy}");
+            }
+            println!(", learning rate {}", stats.learning_rate);
+        }
+
+        Control::Continue
+    }
+
+    fn on_train_end(&mut self, _network: &Network<T>, _stats: &[EpochStats]) {
+        if !self.durations.is_empty() {
+            println!(
+                "Average time to complete one epoch {}s",
+                self.durations.iter().sum::<Duration>().as_millis() as f64
+                    / 1000.0
+                    / self.durations.len() as f64
+            );
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, read, rename},
+    io::Write,
+    time::Duration,
+};
+
+use crate::{
+    element::{Element, ElementType},
+    error::{Error, Result},
+    matrix::Matrix,
+    network::{Network, check_parameter_shapes, element_type_of_file},
+    schedule::{LrSchedule, Scheduler},
+    training::{EpochStats, TrainingConfig},
+};
+
+// Weights and biases of every layer
+type Parameters<T> = (Vec<Matrix<T>>, Vec<Matrix<T>>);
+
+// Everything `Network::try_stochastic_train` needs besides the network to carry on exactly where it
+// left off
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "T: Element")]
+pub(crate) struct TrainingState<T> {
+    pub(crate) rng: ChaCha8Rng,
+    // Training samples in the order of the current epoch, and the held out validation samples
+    pub(crate) order: Vec<usize>,
+    pub(crate) validation: Vec<usize>,
+    pub(crate) scheduler: Scheduler,
+    // Epochs completed, and mini batches of the current epoch completed
+    pub(crate) epoch: u16,
+    pub(crate) batch: usize,
+    // Running totals of the current epoch
+    pub(crate) total_loss: f32,
+    pub(crate) passes: usize,
+    pub(crate) sample_count: usize,
+    pub(crate) learning_rate: f32,
+    // Best monitored value so far, and the weights and biases it was reached with when they are to
+    // be restored
+    pub(crate) best: Option<f32>,
+    pub(crate) best_parameters: Option<Parameters<T>>,
+    pub(crate) epochs_without_improvement: u16,
+    pub(crate) epoch_stats: Vec<EpochStats>,
+    // Time spent training up to this checkpoint
+    #[serde(default)]
+    pub(crate) elapsed: Duration,
+    // Settings the sample order and learning rates were worked out from, a resumed run has to
+    // keep them
+    pub(crate) mini_batch_size: usize,
+    pub(crate) drop_last: bool,
+    pub(crate) validation_split: f32,
+    pub(crate) lr_schedule: LrSchedule,
+    pub(crate) seed: u64,
+}
+
+impl<T> TrainingState<T> {
+    // Only the number of epochs may change when resuming, `seed` is the shuffling seed the config
+    // resolves to
+    pub(crate) fn check_resumable(&self, config: &TrainingConfig, seed: u64) -> Result<()> {
+        let mismatch = |setting: &str, checkpoint: String, config: String| {
+            Err(Error::InvalidConfig(format!(
+                "The checkpoint was trained with {setting} {checkpoint} but the config has {config}"
+            )))
+        };
+
+        if config.mini_batch_size != self.mini_batch_size {
+            return mismatch(
+                "a mini batch size of",
+                self.mini_batch_size.to_string(),
+                config.mini_batch_size.to_string(),
+            );
+        }
+        if config.drop_last != self.drop_last {
+            return mismatch(
+                "drop last",
+                self.drop_last.to_string(),
+                config.drop_last.to_string(),
+            );
+        }
+        if config.validation_split != self.validation_split {
+            return mismatch(
+                "a validation split of",
+                self.validation_split.to_string(),
+                config.validation_split.to_string(),
+            );
+        }
+        if config.lr_schedule != self.lr_schedule {
+            return mismatch(
+                "the learning rate schedule",
+                format!("{:?}", self.lr_schedule),
+                format!("{:?}", config.lr_schedule),
+            );
+        }
+        if seed != self.seed {
+            return mismatch("seed", self.seed.to_string(), seed.to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// Written next to `path` first and renamed over it, so a crash mid write keeps the last checkpoint
+pub(crate) fn save<T: Element>(
+    path: &str,
+    network: &Network<T>,
+    state: &TrainingState<T>,
+) -> Result<()> {
+    let temporary = format!("{path}.tmp");
+    let buf = rmp_serde::to_vec(&(T::ELEMENT_TYPE, network, state))?;
+
+    let mut file = File::create(&temporary)?;
+    file.write_all(&buf)?;
+    file.sync_all()?;
+    rename(&temporary, path)?;
+
+    Ok(())
+}
+
+pub(crate) fn load<T: Element>(path: &str) -> Result<(Network<T>, TrainingState<T>)> {
+    // The element type comes first, like in a saved network
+    let element_type = element_type_of_file(path)?;
+    if element_type != T::ELEMENT_TYPE {
+        return Err(Error::ElementTypeMismatch {
+            expected: T::ELEMENT_TYPE,
+            found: element_type,
+        });
+    }
+
+    let (_, network, state): (ElementType, Network<T>, TrainingState<T>) =
+        rmp_serde::from_slice(&read(path)?)?;
+
+    network.check_structure()?;
+    if let Some((weights, biases)) = &state.best_parameters {
+        if weights.len() != network.weights().len() || biases.len() != network.biases().len() {
+            return Err(Error::CorruptNetwork(format!(
+                "{} best weight matrices and {} bias vectors for {} layers",
+                weights.len(),
+                biases.len(),
+                network.weights().len()
+            )));
+        }
+        check_parameter_shapes(network.layers(), weights, biases)?;
+    }
+
+    Ok((network, state))
+}
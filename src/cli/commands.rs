@@ -0,0 +1,172 @@
+use std::{error::Error, fs};
+
+use neural_network_ml::{
+    Element, ElementType, Matrix, Network,
+    callback::{Callback, ProgressLogger},
+    datasets::mnist::{unpack, unpack_images},
+    metrics::{CsvSink, JsonLinesSink, MetricSink, MetricsLogger},
+    network::element_type_of_file,
+    training::Evaluation,
+};
+
+use super::{EvalArgs, InspectArgs, PredictArgs, TrainArgs, config::Config};
+
+// Calls the generic `$function` with the element type `$element_type` names
+macro_rules! with_element_type {
+    ($element_type:expr, $function:ident($($arg:expr),*)) => {
+        match $element_type {
+            ElementType::F32 => $function::<f32>($($arg),*),
+            ElementType::F64 => $function::<f64>($($arg),*),
+            #[cfg(feature = "half")]
+            ElementType::F16 => $function::<half::f16>($($arg),*),
+            #[cfg(not(feature = "half"))]
+            ElementType::F16 => Err("f16 networks need the `half` feature".into()),
+        }
+    };
+}
+
+pub fn train(args: &TrainArgs) -> Result<(), Box<dyn Error>> {
+    let config = args.config()?;
+    with_element_type!(config.precision, train_as(&config))
+}
+
+fn train_as<T: Element>(config: &Config) -> Result<(), Box<dyn Error>> {
+    let train_data = unpack(&config.dataset.train_images, &config.dataset.train_labels)?;
+    let test_data = unpack(&config.dataset.test_images, &config.dataset.test_labels)?;
+
+    let mut builder = Network::<T>::builder(config.input_size)
+        .layers(
+            config
+                .layers
+                .iter()
+                .map(|layer| (layer.size, layer.activation)),
+        )
+        .loss(config.loss)
+        .learning_rate(config.learning_rate)
+        .optimizer(config.optimizer.cast());
+    if let Some(seed) = config.seed {
+        builder = builder.seed(seed);
+    }
+    let mut network = builder.build();
+
+    let mut progress = ProgressLogger::default();
+    let mut metrics = match &config.metrics {
+        Some(path) if path.ends_with(".csv") => Some(MetricsLogger::new(Box::new(CsvSink::create(
+            path,
+        )?)
+            as Box<dyn MetricSink>)),
+        Some(path) if path.ends_with(".jsonl") => Some(MetricsLogger::new(Box::new(
+            JsonLinesSink::create(path)?,
+        )
+            as Box<dyn MetricSink>)),
+        Some(_) => return Err("Metrics path must end in .csv or .jsonl".into()),
+        None => None,
+    };
+
+    let mut callbacks: Vec<&mut dyn Callback<T>> = vec![&mut progress];
+    if let Some(metrics) = &mut metrics {
+        callbacks.push(metrics);
+    }
+    network.try_stochastic_train_with(
+        &train_data.inputs(),
+        &train_data.targets()?,
+        &config.training,
+        &mut callbacks,
+    )?;
+    if let Some(error) = metrics.as_ref().and_then(|metrics| metrics.error()) {
+        return Err(format!("Failed to write metrics: {error}").into());
+    }
+
+    let test_labels = test_data.targets()?;
+    let accuracy = network.accuracy(
+        &test_data.inputs(),
+        &test_labels,
+        config.training.evaluation,
+    )?;
+    println!("Accuracy: {:.2}%", accuracy * 100.0);
+
+    network.save(&config.output)?;
+    println!("Saved network to {}", config.output);
+
+    Ok(())
+}
+
+pub fn eval(args: &EvalArgs) -> Result<(), Box<dyn Error>> {
+    let config = args.config()?;
+    with_element_type!(element_type_of_file(&args.model)?, eval_as(args, &config))
+}
+
+fn eval_as<T: Element>(args: &EvalArgs, config: &Config) -> Result<(), Box<dyn Error>> {
+    let network = Network::<T>::from_file(&args.model)?;
+    let test_data = unpack(&config.dataset.test_images, &config.dataset.test_labels)?;
+
+    let test_labels = test_data.targets()?;
+    if let Evaluation::Regression { .. } = config.training.evaluation {
+        let accuracy = network.accuracy(
+            &test_data.inputs(),
+            &test_labels,
+            config.training.evaluation,
+        )?;
+        println!("Accuracy: {:.2}%", accuracy * 100.0);
+        return Ok(());
+    }
+
+    let report = network.evaluate(&test_data.inputs(), &test_labels, args.top_k)?;
+    println!("{report}");
+
+    if let Some(path) = &args.report {
+        let contents = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => report.to_json(),
+            Some("csv") => report.to_csv(),
+            _ => return Err("Report path must end in .json or .csv".into()),
+        };
+        fs::write(path, contents)?;
+        println!("Saved report to {}", path.display());
+    }
+
+    Ok(())
+}
+
+pub fn predict(args: &PredictArgs) -> Result<(), Box<dyn Error>> {
+    with_element_type!(element_type_of_file(&args.model)?, predict_as(args))
+}
+
+fn predict_as<T: Element>(args: &PredictArgs) -> Result<(), Box<dyn Error>> {
+    let network = Network::<T>::from_file(&args.model)?;
+    let images = unpack_images(&args.images)?;
+
+    for (i, image) in images.iter().enumerate().skip(args.start).take(args.count) {
+        let inputs: Vec<T> = image.iter().map(|val| T::from_f32(*val as f32)).collect();
+        let outputs = network.try_feed_forward(&inputs)?;
+        let prediction = Matrix::from(outputs.clone()).transpose().argmax_per_col()[0];
+
+        println!("Image {i}: {prediction} {outputs:?}");
+    }
+
+    Ok(())
+}
+
+pub fn inspect(args: &InspectArgs) -> Result<(), Box<dyn Error>> {
+    with_element_type!(element_type_of_file(&args.model)?, inspect_as(args))
+}
+
+fn inspect_as<T: Element>(args: &InspectArgs) -> Result<(), Box<dyn Error>> {
+    let network = Network::<T>::from_file(&args.model)?;
+
+    println!("Input size:    {}", network.layers()[0]);
+    for (i, (size, activation)) in network.layers()[1..]
+        .iter()
+        .zip(network.activations())
+        .enumerate()
+    {
+        println!("Layer {}:       {size} {activation:?}", i + 1);
+    }
+    println!("Parameters:    {}", network.parameter_count());
+    println!("Element type:  {}", network.element_type());
+    println!("Loss:          {:?}", network.loss_function());
+    println!("Optimizer:     {}", network.optimizer().name());
+    println!("Learning rate: {}", network.learning_rate());
+    println!("Seed:          {}", network.seed());
+
+    Ok(())
+}
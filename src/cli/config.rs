@@ -0,0 +1,364 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use neural_network_ml::{
+    ActivationFunction, ElementType, Loss,
+    optimizer::{Adagrad, Adam, AdamW, Nesterov, OptimizerKind, RmsProp, Sgd},
+    schedule::LrSchedule,
+    training::TrainingConfig,
+};
+
+// Everything needed to run an experiment, read from a TOML or JSON file and overridden by flags
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub input_size: usize,
+    pub layers: Vec<LayerConfig>,
+    pub loss: Loss,
+    pub optimizer: OptimizerKind,
+    pub learning_rate: f32,
+    pub seed: Option<u64>,
+    // Element type the network is built from and trained in
+    pub precision: ElementType,
+    pub training: TrainingConfig,
+    pub dataset: DatasetConfig,
+    pub output: String,
+    // Per epoch metrics log, CSV or JSON Lines depending on the extension
+    pub metrics: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LayerConfig {
+    pub size: usize,
+    pub activation: ActivationFunction,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatasetConfig {
+    pub train_images: String,
+    pub train_labels: String,
+    pub test_images: String,
+    pub test_labels: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            input_size: 784,
+            layers: vec![
+                LayerConfig {
+                    size: 30,
+                    activation: ActivationFunction::Sigmoid,
+                },
+                LayerConfig {
+                    size: 10,
+                    activation: ActivationFunction::Sigmoid,
+                },
+            ],
+            loss: Loss::MeanSquaredError,
+            optimizer: OptimizerKind::default(),
+            learning_rate: 1.0,
+            seed: None,
+            precision: ElementType::F32,
+            training: TrainingConfig::default(),
+            dataset: DatasetConfig::default(),
+            output: String::from("network.mpk"),
+            metrics: None,
+        }
+    }
+}
+
+impl Default for DatasetConfig {
+    fn default() -> Self {
+        DatasetConfig {
+            train_images: String::from("mnist/train-images.idx3-ubyte"),
+            train_labels: String::from("mnist/train-labels.idx1-ubyte"),
+            test_images: String::from("mnist/t10k-images.idx3-ubyte"),
+            test_labels: String::from("mnist/t10k-labels.idx1-ubyte"),
+        }
+    }
+}
+
+impl Config {
+    // The format is picked from the extension, `.json` is JSON and anything else TOML
+    pub fn from_file(path: &Path) -> Result<Config, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            serde_json::from_str(&contents)
+                .map_err(|error| format!("Invalid config {}: {error}", path.display()))
+        } else {
+            toml::from_str(&contents)
+                .map_err(|error| format!("Invalid config {}: {error}", path.display()))
+        }
+    }
+
+    // Catches architectures the network builder would panic on or silently train as a dead end
+    pub fn validate(&self) -> Result<(), String> {
+        if self.input_size == 0 {
+            return Err(String::from("The input size must be at least 1"));
+        }
+        if self.layers.is_empty() {
+            return Err(String::from("A network needs at least one layer"));
+        }
+        if let Some(i) = self.layers.iter().position(|layer| layer.size == 0) {
+            return Err(format!("Layer {} has no neurons", i + 1));
+        }
+
+        Ok(())
+    }
+}
+
+// Parses `SIZE:ACTIVATION`, e.g. `30:relu` or `10:leaky_relu=0.01`
+pub fn parse_layer(value: &str) -> Result<LayerConfig, String> {
+    let (size, activation) = value
+        .split_once(':')
+        .ok_or_else(|| format!("Expected SIZE:ACTIVATION, found `{value}`"))?;
+
+    Ok(LayerConfig {
+        size: size
+            .parse()
+            .map_err(|_| format!("Invalid layer size `{size}`"))?,
+        activation: parse_activation(activation)?,
+    })
+}
+
+pub fn parse_activation(value: &str) -> Result<ActivationFunction, String> {
+    let (name, parameter) = split_parameter(value)?;
+
+    Ok(match name {
+        "identity" => ActivationFunction::Identity,
+        "sigmoid" => ActivationFunction::Sigmoid,
+        "tanh" => ActivationFunction::Tanh,
+        "relu" => ActivationFunction::Relu,
+        "leaky_relu" => ActivationFunction::LeakyRelu {
+            slope: parameter.unwrap_or(0.01),
+        },
+        "elu" => ActivationFunction::Elu {
+            alpha: parameter.unwrap_or(1.0),
+        },
+        "selu" => ActivationFunction::Selu,
+        "gelu" => ActivationFunction::Gelu,
+        "swish" => ActivationFunction::Swish,
+        "softplus" => ActivationFunction::Softplus,
+        "softmax" => ActivationFunction::Softmax,
+        _ => return Err(format!("Unknown activation `{name}`")),
+    })
+}
+
+pub fn parse_loss(value: &str) -> Result<Loss, String> {
+    let (name, parameter) = split_parameter(value)?;
+
+    Ok(match name {
+        "mse" => Loss::MeanSquaredError,
+        "mae" => Loss::MeanAbsoluteError,
+        "huber" => Loss::Huber {
+            delta: parameter.unwrap_or(1.0),
+        },
+        "bce" => Loss::BinaryCrossEntropy,
+        "cross_entropy" => Loss::CrossEntropy,
+        _ => return Err(format!("Unknown loss `{name}`")),
+    })
+}
+
+// The optional parameter is the momentum for SGD and Nesterov and the weight decay for AdamW
+pub fn parse_optimizer(value: &str) -> Result<OptimizerKind, String> {
+    let (name, parameter) = split_parameter(value)?;
+
+    Ok(match name {
+        "sgd" => OptimizerKind::Sgd(Sgd::new(parameter.unwrap_or(0.0))),
+        "nesterov" => OptimizerKind::Nesterov(Nesterov::new(parameter.unwrap_or(0.9))),
+        "adam" => OptimizerKind::Adam(Adam::default()),
+        "adamw" => {
+            let mut optimizer = AdamW::default();
+            if let Some(weight_decay) = parameter {
+                optimizer.weight_decay = weight_decay;
+            }
+            OptimizerKind::AdamW(optimizer)
+        }
+        "rmsprop" => OptimizerKind::RmsProp(RmsProp::default()),
+        "adagrad" => OptimizerKind::Adagrad(Adagrad::default()),
+        _ => return Err(format!("Unknown optimizer `{name}`")),
+    })
+}
+
+// The optional parameter is the decay factor for step, exponential and plateau, the period in
+// epochs for cosine and the peak rate for one-cycle
+pub fn parse_lr_schedule(value: &str) -> Result<LrSchedule, String> {
+    let (name, parameter) = split_parameter(value)?;
+
+    Ok(match name {
+        "constant" => LrSchedule::Constant,
+        "step" => LrSchedule::StepDecay {
+            step_size: 10,
+            gamma: parameter.unwrap_or(0.1),
+        },
+        "exponential" => LrSchedule::Exponential {
+            gamma: parameter.unwrap_or(0.95),
+        },
+        "cosine" => LrSchedule::CosineAnnealing {
+            period: parameter.map_or(10, |period| period as u32),
+            period_multiplier: 2,
+            min_lr: 0.0,
+        },
+        "one_cycle" => LrSchedule::OneCycle {
+            max_lr: parameter.unwrap_or(0.1),
+            warmup_fraction: 0.3,
+            div_factor: 25.0,
+            final_div_factor: 1e4,
+        },
+        "plateau" => LrSchedule::ReduceOnPlateau {
+            factor: parameter.unwrap_or(0.1),
+            patience: 5,
+            min_delta: 0.0,
+            min_lr: 0.0,
+        },
+        _ => return Err(format!("Unknown learning rate schedule `{name}`")),
+    })
+}
+
+pub fn parse_precision(value: &str) -> Result<ElementType, String> {
+    match value {
+        "f16" => Ok(ElementType::F16),
+        "f32" => Ok(ElementType::F32),
+        "f64" => Ok(ElementType::F64),
+        _ => Err(format!("Unknown precision `{value}`")),
+    }
+}
+
+// Splits `name=parameter` into its name and optional numeric parameter
+fn split_parameter(value: &str) -> Result<(&str, Option<f32>), String> {
+    match value.split_once('=') {
+        Some((name, parameter)) => {
+            let parameter = parameter
+                .parse()
+                .map_err(|_| format!("Invalid parameter `{parameter}` for `{name}`"))?;
+            Ok((name, Some(parameter)))
+        }
+        None => Ok((value, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use clap::Parser;
+
+    use super::*;
+    use crate::cli::{Cli, Command};
+
+    fn train_config(args: &[&str]) -> Result<Config, String> {
+        let cli = Cli::try_parse_from(["neural-network-ml", "train"].iter().chain(args))
+            .map_err(|error| error.to_string())?;
+
+        match cli.command {
+            Command::Train(args) => args.config(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn parses_layers() {
+        let layer = parse_layer("30:relu").unwrap();
+        assert_eq!(layer.size, 30);
+        assert_eq!(layer.activation, ActivationFunction::Relu);
+
+        let layer = parse_layer("10:leaky_relu=0.2").unwrap();
+        assert_eq!(
+            layer.activation,
+            ActivationFunction::LeakyRelu { slope: 0.2 }
+        );
+
+        assert!(parse_layer("30").is_err());
+        assert!(parse_layer("many:relu").is_err());
+        assert!(parse_layer("30:cubic").is_err());
+        assert!(parse_layer("30:elu=high").is_err());
+    }
+
+    #[test]
+    fn parses_optimizers() {
+        assert!(matches!(
+            parse_optimizer("sgd"),
+            Ok(OptimizerKind::Sgd(Sgd { momentum: 0.0, .. }))
+        ));
+        assert!(matches!(
+            parse_optimizer("nesterov=0.5"),
+            Ok(OptimizerKind::Nesterov(optimizer)) if optimizer.momentum == 0.5
+        ));
+        assert!(matches!(
+            parse_optimizer("adamw=0.1"),
+            Ok(OptimizerKind::AdamW(AdamW {
+                weight_decay: 0.1,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            parse_optimizer("adam"),
+            Ok(OptimizerKind::Adam(_))
+        ));
+        assert!(parse_optimizer("lbfgs").is_err());
+    }
+
+    #[test]
+    fn parses_lr_schedules() {
+        assert_eq!(parse_lr_schedule("constant"), Ok(LrSchedule::Constant));
+        assert_eq!(
+            parse_lr_schedule("exponential=0.5"),
+            Ok(LrSchedule::Exponential { gamma: 0.5 })
+        );
+        assert!(matches!(
+            parse_lr_schedule("cosine=4"),
+            Ok(LrSchedule::CosineAnnealing { period: 4, .. })
+        ));
+        assert!(parse_lr_schedule("linear").is_err());
+        assert!(parse_lr_schedule("step=").is_err());
+    }
+
+    #[test]
+    fn flags_take_precedence_over_the_config() {
+        let path = env::temp_dir().join(format!("cli-config-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "input_size = 4\nlearning_rate = 0.5\n\n[[layers]]\nsize = 3\nactivation = \"Tanh\"\n\n\
+             [training]\nepochs = 7\nmini_batch_size = 2\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+
+        let from_file = train_config(&["--config", path]);
+        let overridden = train_config(&["--config", path, "--epochs", "2", "--layers", "5:relu"]);
+        fs::remove_file(path).unwrap();
+
+        let from_file = from_file.unwrap();
+        assert_eq!((from_file.input_size, from_file.learning_rate), (4, 0.5));
+        assert_eq!(from_file.training.epochs, 7);
+        assert_eq!(from_file.layers[0].activation, ActivationFunction::Tanh);
+
+        let overridden = overridden.unwrap();
+        assert_eq!(overridden.training.epochs, 2);
+        assert_eq!(overridden.training.mini_batch_size, 2);
+        assert_eq!(overridden.layers.len(), 1);
+        assert_eq!(overridden.layers[0].size, 5);
+        assert_eq!(overridden.layers[0].activation, ActivationFunction::Relu);
+    }
+
+    #[test]
+    fn empty_architectures_are_rejected() {
+        assert!(train_config(&["--layers", "0:relu,10:sigmoid"]).is_err());
+        assert!(train_config(&["--input-size", "0"]).is_err());
+        assert!(train_config(&["--layers", "10:sigmoid"]).is_ok());
+
+        let config = Config {
+            layers: Vec::new(),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,263 @@
+pub mod commands;
+pub mod config;
+
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use neural_network_ml::{
+    ElementType, Loss,
+    optimizer::OptimizerKind,
+    schedule::LrSchedule,
+    training::{CheckpointInterval, Checkpointing, EarlyStopping},
+};
+
+use config::{
+    Config, LayerConfig, parse_layer, parse_loss, parse_lr_schedule, parse_optimizer,
+    parse_precision,
+};
+
+/// Train and run feed forward neural networks on MNIST
+#[derive(Parser)]
+#[command(version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Train a new network and save it
+    Train(Box<TrainArgs>),
+    /// Score a saved network against a labelled dataset
+    Eval(EvalArgs),
+    /// Print the predictions of a saved network for unlabelled images
+    Predict(PredictArgs),
+    /// Print the architecture and hyperparameters of a saved network
+    Inspect(InspectArgs),
+}
+
+#[derive(Args)]
+pub struct TrainArgs {
+    /// TOML or JSON experiment config, flags take precedence over its values
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    #[arg(long)]
+    pub input_size: Option<usize>,
+    /// Comma separated SIZE:ACTIVATION pairs, e.g. 128:relu,10:softmax
+    #[arg(long, value_delimiter = ',', value_parser = parse_layer)]
+    pub layers: Option<Vec<LayerConfig>>,
+    /// mse, mae, huber[=DELTA], bce or cross_entropy
+    #[arg(long, value_parser = parse_loss)]
+    pub loss: Option<Loss>,
+    /// sgd[=MOMENTUM], nesterov[=MOMENTUM], adam, adamw[=WEIGHT_DECAY], rmsprop or adagrad
+    #[arg(long, value_parser = parse_optimizer)]
+    pub optimizer: Option<OptimizerKind>,
+    #[arg(long)]
+    pub learning_rate: Option<f32>,
+    #[arg(long)]
+    pub epochs: Option<u16>,
+    #[arg(long)]
+    pub batch_size: Option<usize>,
+    #[arg(long)]
+    pub drop_last: bool,
+    /// Fraction of the training images held out for validation after every epoch
+    #[arg(long)]
+    pub validation_split: Option<f32>,
+    /// Stop once the validation loss has not improved for this many epochs
+    #[arg(long)]
+    pub patience: Option<u16>,
+    /// constant, step[=GAMMA], exponential[=GAMMA], cosine[=PERIOD], one_cycle[=MAX_LR] or
+    /// plateau[=FACTOR]
+    #[arg(long, value_parser = parse_lr_schedule)]
+    pub lr_schedule: Option<LrSchedule>,
+    /// Ramp the learning rate up linearly over this many mini batches first
+    #[arg(long)]
+    pub warmup_steps: Option<u64>,
+    /// Write a checkpoint to resume from to this path while training
+    #[arg(long)]
+    pub checkpoint: Option<String>,
+    /// Epochs between checkpoints, 1 unless the config says otherwise
+    #[arg(long)]
+    pub checkpoint_every: Option<u16>,
+    /// Carry on training from a checkpoint, the architecture and precision have to match
+    #[arg(long)]
+    pub resume_from: Option<String>,
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Element type to train in, f32, f64 or f16 (needs the `half` feature)
+    #[arg(long, value_parser = parse_precision)]
+    pub precision: Option<ElementType>,
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+    #[arg(long, short)]
+    pub output: Option<String>,
+    /// Write per epoch metrics to a .csv or .jsonl file
+    #[arg(long)]
+    pub metrics: Option<String>,
+}
+
+#[derive(Args)]
+pub struct DatasetArgs {
+    #[arg(long)]
+    pub train_images: Option<String>,
+    #[arg(long)]
+    pub train_labels: Option<String>,
+    #[arg(long)]
+    pub test_images: Option<String>,
+    #[arg(long)]
+    pub test_labels: Option<String>,
+}
+
+#[derive(Args)]
+pub struct EvalArgs {
+    pub model: String,
+    /// Config to read the test dataset paths from
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// Count a sample as correct for top-k accuracy when its label is among the K largest outputs
+    #[arg(long, default_value_t = 5)]
+    pub top_k: usize,
+    /// Also write the report to a .json or .csv file
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+    #[command(flatten)]
+    pub dataset: DatasetArgs,
+}
+
+#[derive(Args)]
+pub struct PredictArgs {
+    pub model: String,
+    /// MNIST formatted images file
+    pub images: String,
+    /// Index of the first image to predict
+    #[arg(long, default_value_t = 0)]
+    pub start: usize,
+    /// Number of images to predict
+    #[arg(long, default_value_t = 10)]
+    pub count: usize,
+}
+
+#[derive(Args)]
+pub struct InspectArgs {
+    pub model: String,
+}
+
+impl TrainArgs {
+    pub fn config(&self) -> Result<Config, String> {
+        let mut config = load_config(self.config.as_ref())?;
+
+        if let Some(input_size) = self.input_size {
+            config.input_size = input_size;
+        }
+        if let Some(layers) = &self.layers {
+            config.layers = layers.clone();
+        }
+        if let Some(loss) = self.loss {
+            config.loss = loss;
+        }
+        if let Some(optimizer) = &self.optimizer {
+            config.optimizer = optimizer.clone();
+        }
+        if let Some(learning_rate) = self.learning_rate {
+            config.learning_rate = learning_rate;
+        }
+        if let Some(epochs) = self.epochs {
+            config.training.epochs = epochs;
+        }
+        if let Some(batch_size) = self.batch_size {
+            config.training.mini_batch_size = batch_size;
+        }
+        if self.drop_last {
+            config.training.drop_last = true;
+        }
+        if let Some(validation_split) = self.validation_split {
+            config.training.validation_split = validation_split;
+        }
+        if let Some(patience) = self.patience {
+            config
+                .training
+                .early_stopping
+                .get_or_insert_with(EarlyStopping::default)
+                .patience = patience;
+        }
+        if let Some(lr_schedule) = &self.lr_schedule {
+            config.training.lr_schedule = lr_schedule.clone();
+        }
+        if let Some(steps) = self.warmup_steps {
+            let then = Box::new(config.training.lr_schedule.clone());
+            config.training.lr_schedule = LrSchedule::Warmup { steps, then };
+        }
+        if let Some(path) = &self.checkpoint {
+            match &mut config.training.checkpoint {
+                Some(checkpointing) => checkpointing.path = path.clone(),
+                None => {
+                    config.training.checkpoint = Some(Checkpointing {
+                        path: path.clone(),
+                        every: CheckpointInterval::Epochs(1),
+                    })
+                }
+            }
+        }
+        if let Some(epochs) = self.checkpoint_every {
+            let checkpointing = config
+                .training
+                .checkpoint
+                .as_mut()
+                .ok_or("--checkpoint-every needs a checkpoint path")?;
+            checkpointing.every = CheckpointInterval::Epochs(epochs);
+        }
+        if let Some(path) = &self.resume_from {
+            config.training.resume_from = Some(path.clone());
+        }
+        if let Some(seed) = self.seed {
+            config.seed = Some(seed);
+        }
+        if let Some(precision) = self.precision {
+            config.precision = precision;
+        }
+        if let Some(output) = &self.output {
+            config.output = output.clone();
+        }
+        if let Some(metrics) = &self.metrics {
+            config.metrics = Some(metrics.clone());
+        }
+        self.dataset.apply(&mut config);
+
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl EvalArgs {
+    pub fn config(&self) -> Result<Config, String> {
+        let mut config = load_config(self.config.as_ref())?;
+        self.dataset.apply(&mut config);
+        Ok(config)
+    }
+}
+
+impl DatasetArgs {
+    fn apply(&self, config: &mut Config) {
+        let dataset = &mut config.dataset;
+        let overrides = [
+            (&self.train_images, &mut dataset.train_images),
+            (&self.train_labels, &mut dataset.train_labels),
+            (&self.test_images, &mut dataset.test_images),
+            (&self.test_labels, &mut dataset.test_labels),
+        ];
+
+        for (flag, value) in overrides {
+            if let Some(flag) = flag {
+                *value = flag.clone();
+            }
+        }
+    }
+}
+
+fn load_config(path: Option<&PathBuf>) -> Result<Config, String> {
+    match path {
+        Some(path) => Config::from_file(path),
+        None => Ok(Config::default()),
+    }
+}
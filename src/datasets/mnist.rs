@@ -0,0 +1,130 @@
+use std::{fs::File, io::Read};
+
+use crate::{
+    element::Element,
+    error::{Error, Result},
+};
+
+const IMAGES_MAGIC_NUMBER: u32 = 0x00000803;
+const LABELS_MAGIC_NUMBER: u32 = 0x00000801;
+// Magic number and count, followed by the rows and columns of every image in an images file
+const IMAGES_HEADER_LEN: u64 = 16;
+const LABELS_HEADER_LEN: u64 = 8;
+
+pub const CLASSES: usize = 10;
+
+pub struct MnistImages {
+    pub images: Vec<Vec<u8>>,
+    pub labels: Vec<u8>,
+}
+
+impl MnistImages {
+    // Raw pixel values as network inputs
+    pub fn inputs<T: Element>(&self) -> Vec<Vec<T>> {
+        self.images
+            .iter()
+            .map(|vec| vec.iter().map(|val| T::from_f32(*val as f32)).collect())
+            .collect()
+    }
+
+    // One-hot encoded labels as expected network outputs
+    pub fn targets<T: Element>(&self) -> Result<Vec<Vec<T>>> {
+        one_hot(&self.labels, CLASSES)
+    }
+}
+
+pub fn unpack<T: AsRef<str>>(images_filename: T, labels_filename: T) -> Result<MnistImages> {
+    let mut images_file = File::open(images_filename.as_ref())?;
+    let mut labels_file = File::open(labels_filename.as_ref())?;
+    let (images_len, pixels) = read_images_header(&mut images_file)?;
+
+    check_magic_number(LABELS_MAGIC_NUMBER, read_u32(&mut labels_file)?)?;
+    let labels_len = read_u32(&mut labels_file)? as usize;
+    if images_len != labels_len {
+        return Err(Error::LengthMismatch {
+            expected: images_len,
+            found: labels_len,
+        });
+    }
+    check_remaining(&labels_file, LABELS_HEADER_LEN, labels_len)?;
+
+    let images = read_images(&mut images_file, images_len, pixels)?;
+    let mut labels = vec![0u8; labels_len];
+    labels_file.read_exact(&mut labels)?;
+
+    Ok(MnistImages { images, labels })
+}
+
+// Reads an images file on its own, for inputs that have no labels
+pub fn unpack_images<T: AsRef<str>>(images_filename: T) -> Result<Vec<Vec<u8>>> {
+    let mut images_file = File::open(images_filename.as_ref())?;
+    let (images_len, pixels) = read_images_header(&mut images_file)?;
+
+    read_images(&mut images_file, images_len, pixels)
+}
+
+// Number of images and pixels per image, after checking the file holds that many. Counts come
+// from the file, so nothing is allocated from them before the check
+fn read_images_header(file: &mut File) -> Result<(usize, usize)> {
+    check_magic_number(IMAGES_MAGIC_NUMBER, read_u32(file)?)?;
+    let images_len = read_u32(file)? as usize;
+    let rows = read_u32(file)? as usize;
+    let cols = read_u32(file)? as usize;
+
+    let pixels = rows.saturating_mul(cols);
+    check_remaining(file, IMAGES_HEADER_LEN, images_len.saturating_mul(pixels))?;
+
+    Ok((images_len, pixels))
+}
+
+fn read_images(file: &mut File, images_len: usize, pixels: usize) -> Result<Vec<Vec<u8>>> {
+    let mut data = vec![0u8; images_len * pixels];
+    file.read_exact(&mut data)?;
+
+    Ok((0..images_len)
+        .map(|i| data[i * pixels..(i + 1) * pixels].to_vec())
+        .collect())
+}
+
+fn read_u32(file: &mut File) -> Result<u32> {
+    let mut bytes = [0u8; 4];
+    file.read_exact(&mut bytes)?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+// Errors if the file is too short for `expected` bytes past its header
+fn check_remaining(file: &File, header_len: u64, expected: usize) -> Result<()> {
+    let found = file.metadata()?.len().saturating_sub(header_len);
+    if (found as usize) < expected {
+        return Err(Error::LengthMismatch {
+            expected,
+            found: found as usize,
+        });
+    }
+
+    Ok(())
+}
+
+// Encodes each label as a vector with a 1.0 at the index of the label
+pub fn one_hot<T: Element>(labels: &[u8], classes: usize) -> Result<Vec<Vec<T>>> {
+    labels
+        .iter()
+        .map(|&label| {
+            if label as usize >= classes {
+                return Err(Error::LabelOutOfRange { label, classes });
+            }
+
+            let mut encoded = vec![T::zero(); classes];
+            encoded[label as usize] = T::one();
+            Ok(encoded)
+        })
+        .collect()
+}
+
+fn check_magic_number(expected: u32, found: u32) -> Result<()> {
+    if found == expected {
+        Ok(())
+    } else {
+        Err(Error::BadMagicNumber { expected, found })
+    }
+}
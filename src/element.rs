@@ -0,0 +1,133 @@
+use std::{
+    fmt::{Debug, Display},
+    iter::Sum,
+    ops::{AddAssign, DivAssign, MulAssign, SubAssign},
+};
+
+use num_traits::Float;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::gemm::{Kernel, kernel_scalar};
+
+// Floating point types a matrix or network can be built from, recorded in saved networks
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElementType {
+    F16,
+    F32,
+    F64,
+}
+
+impl Display for ElementType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ElementType::F16 => write!(f, "f16"),
+            ElementType::F32 => write!(f, "f32"),
+            ElementType::F64 => write!(f, "f64"),
+        }
+    }
+}
+
+pub trait Element:
+    Float
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + Default
+    + Debug
+    + Send
+    + Sync
+    + Serialize
+    + DeserializeOwned
+    + 'static
+{
+    const ELEMENT_TYPE: ElementType;
+
+    // Hyperparameters are stored as f32 whatever the element type
+    fn from_f32(value: f32) -> Self;
+
+    fn from_f64(value: f64) -> Self;
+
+    // Lossy for f64, used for reporting metrics
+    fn as_f32(self) -> f32;
+
+    fn as_f64(self) -> f64;
+
+    // Micro-kernel used by the blocked matrix multiplication
+    fn kernel() -> Kernel<Self> {
+        kernel_scalar
+    }
+}
+
+impl Element for f32 {
+    const ELEMENT_TYPE: ElementType = ElementType::F32;
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn as_f32(self) -> f32 {
+        self
+    }
+
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+
+    // Only f32 has a SIMD kernel, selected when the CPU supports it
+    fn kernel() -> Kernel<Self> {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            return crate::gemm::kernel_avx2_fma;
+        }
+
+        kernel_scalar
+    }
+}
+
+impl Element for f64 {
+    const ELEMENT_TYPE: ElementType = ElementType::F64;
+
+    fn from_f32(value: f32) -> Self {
+        value as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn as_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
+
+#[cfg(feature = "half")]
+impl Element for half::f16 {
+    const ELEMENT_TYPE: ElementType = ElementType::F16;
+
+    fn from_f32(value: f32) -> Self {
+        half::f16::from_f32(value)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        half::f16::from_f64(value)
+    }
+
+    fn as_f32(self) -> f32 {
+        self.to_f32()
+    }
+
+    fn as_f64(self) -> f64 {
+        self.to_f64()
+    }
+}
@@ -0,0 +1,110 @@
+use std::{fmt, io};
+
+use crate::element::ElementType;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Decode(rmp_serde::decode::Error),
+    Encode(rmp_serde::encode::Error),
+    // `operation` reads as a verb, e.g. "add" or "multiply"
+    ShapeMismatch {
+        operation: &'static str,
+        lhs: (usize, usize),
+        rhs: (usize, usize),
+    },
+    LengthMismatch {
+        expected: usize,
+        found: usize,
+    },
+    BadMagicNumber {
+        expected: u32,
+        found: u32,
+    },
+    LabelOutOfRange {
+        label: u8,
+        classes: usize,
+    },
+    ElementTypeMismatch {
+        expected: ElementType,
+        found: ElementType,
+    },
+    // Layer sizes, including the input layer, of a network and of a checkpoint resumed into it
+    ArchitectureMismatch {
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+    // A decoded network whose layer sizes, parameters and activations don't agree
+    CorruptNetwork(String),
+    // Training settings that cannot work with the given data, e.g. a zero mini batch size
+    InvalidConfig(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "IO error: {error}"),
+            Error::Decode(error) => write!(f, "Failed to decode network: {error}"),
+            Error::Encode(error) => write!(f, "Failed to encode network: {error}"),
+            Error::ShapeMismatch {
+                operation,
+                lhs,
+                rhs,
+            } => write!(
+                f,
+                "Attempt to {operation} matrix of size {}x{} with matrix of size {}x{}",
+                lhs.0, lhs.1, rhs.0, rhs.1
+            ),
+            Error::LengthMismatch { expected, found } => {
+                write!(f, "Expected {expected} elements but found {found}")
+            }
+            Error::BadMagicNumber { expected, found } => write!(
+                f,
+                "Bad magic number, expected {expected:#010x} but found {found:#010x}"
+            ),
+            Error::LabelOutOfRange { label, classes } => {
+                write!(f, "Label {label} is out of range for {classes} classes")
+            }
+            Error::ElementTypeMismatch { expected, found } => {
+                write!(f, "Attempt to load {found} network as {expected}")
+            }
+            Error::ArchitectureMismatch { expected, found } => write!(
+                f,
+                "Attempt to resume a network with layers {found:?} into one with layers {expected:?}"
+            ),
+            Error::CorruptNetwork(reason) => write!(f, "Corrupt network: {reason}"),
+            Error::InvalidConfig(reason) => write!(f, "Invalid training config: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Decode(error) => Some(error),
+            Error::Encode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<rmp_serde::decode::Error> for Error {
+    fn from(error: rmp_serde::decode::Error) -> Self {
+        Error::Decode(error)
+    }
+}
+
+impl From<rmp_serde::encode::Error> for Error {
+    fn from(error: rmp_serde::encode::Error) -> Self {
+        Error::Encode(error)
+    }
+}
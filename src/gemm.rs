@@ -0,0 +1,280 @@
+use rayon::prelude::*;
+use std::{any::Any, cell::RefCell, thread::LocalKey};
+
+use crate::element::Element;
+
+// Micro tile computed by one kernel call, 16 columns fill two AVX registers
+const MR: usize = 4;
+const NR: usize = 16;
+// Rows of the result handled per task and depth of each packed block, sized to stay in cache
+const MC: usize = 64;
+const KC: usize = 256;
+// Below this many multiply-adds packing costs more than it saves
+const SMALL: usize = 32 * 32 * 32;
+
+// Packing buffers of every element type used on a thread, kept between calls so repeated products
+// of the same size do not allocate
+type Buffers = RefCell<Vec<Box<dyn Any>>>;
+
+thread_local! {
+    static PACKED_A: Buffers = const { RefCell::new(Vec::new()) };
+    static PACKED_B: Buffers = const { RefCell::new(Vec::new()) };
+}
+
+// Multiplies a packed MR tall panel of `a` by a packed NR wide panel of `b` over `kc` steps
+pub type Kernel<T> = fn(usize, &[T], &[T], &mut [[T; NR]; MR]);
+
+// A read only matrix whose element (i, j) is `data[i * row_stride + j * col_stride]`
+#[derive(Clone, Copy)]
+pub struct Operand<'a, T = f32> {
+    pub data: &'a [T],
+    pub row_stride: usize,
+    pub col_stride: usize,
+}
+
+impl<'a, T: Element> Operand<'a, T> {
+    pub fn row_major(data: &'a [T], cols: usize) -> Self {
+        Operand {
+            data,
+            row_stride: cols,
+            col_stride: 1,
+        }
+    }
+
+    // The same data read with rows and columns swapped, no copy is made
+    pub fn transpose(self) -> Self {
+        Operand {
+            data: self.data,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+        }
+    }
+
+    #[inline(always)]
+    fn get(&self, i: usize, j: usize) -> T {
+        self.data[i * self.row_stride + j * self.col_stride]
+    }
+}
+
+// Adds the (m x k) by (k x n) product of `a` and `b` to the row-major (m x n) matrix `c`
+pub fn gemm<T: Element>(m: usize, n: usize, k: usize, a: Operand<T>, b: Operand<T>, c: &mut [T]) {
+    assert!(
+        c.len() >= m * n,
+        "Result buffer is too small for a {m}x{n} product"
+    );
+    if m == 0 || n == 0 || k == 0 {
+        return;
+    }
+
+    if m * n * k <= SMALL {
+        gemm_small(m, n, k, a, b, c);
+        return;
+    }
+
+    let kernel = T::kernel();
+    let c = &mut c[..m * n];
+
+    with_buffer(&PACKED_B, n.div_ceil(NR) * NR * KC.min(k), |packed_b| {
+        for p0 in (0..k).step_by(KC) {
+            let kc = KC.min(k - p0);
+            pack_b(b, p0, kc, n, packed_b);
+            let packed_b = &*packed_b;
+
+            c.par_chunks_mut(MC * n)
+                .enumerate()
+                .for_each(|(block, c_block)| {
+                    let i0 = block * MC;
+                    let mc = c_block.len() / n;
+                    with_buffer(&PACKED_A, mc.div_ceil(MR) * MR * kc, |packed_a| {
+                        pack_a(a, i0, mc, p0, kc, packed_a);
+
+                        for jr in (0..n).step_by(NR) {
+                            let nr = NR.min(n - jr);
+                            let b_panel = &packed_b[jr * kc..(jr + NR) * kc];
+
+                            for ir in (0..mc).step_by(MR) {
+                                let mr = MR.min(mc - ir);
+                                let a_panel = &packed_a[ir * kc..(ir + MR) * kc];
+
+                                let mut acc = [[T::zero(); NR]; MR];
+                                kernel(kc, a_panel, b_panel, &mut acc);
+
+                                for (i, acc_row) in acc.iter().enumerate().take(mr) {
+                                    let c_row =
+                                        &mut c_block[(ir + i) * n + jr..(ir + i) * n + jr + nr];
+                                    c_row
+                                        .iter_mut()
+                                        .zip(acc_row)
+                                        .for_each(|(c, acc)| *c += *acc);
+                                }
+                            }
+                        }
+                    });
+                });
+        }
+    });
+}
+
+// Runs `f` on a `len` long buffer from `buffers`, growing it when needed. The contents are
+// whatever the last product left, so `f` has to overwrite them
+fn with_buffer<T: Element, R>(
+    buffers: &'static LocalKey<Buffers>,
+    len: usize,
+    f: impl FnOnce(&mut [T]) -> R,
+) -> R {
+    buffers.with(|buffers| {
+        // Taken further up the stack when rayon runs another product on this thread while waiting
+        let Ok(mut buffers) = buffers.try_borrow_mut() else {
+            return f(&mut vec![T::zero(); len]);
+        };
+
+        let index = match buffers.iter().position(|buffer| buffer.is::<Vec<T>>()) {
+            Some(index) => index,
+            None => {
+                buffers.push(Box::new(Vec::<T>::new()));
+                buffers.len() - 1
+            }
+        };
+        let buffer = buffers[index]
+            .downcast_mut::<Vec<T>>()
+            .expect("Buffer was found by its type");
+        if buffer.len() < len {
+            buffer.resize(len, T::zero());
+        }
+
+        f(&mut buffer[..len])
+    })
+}
+
+// The straightforward i-j-k product the blocked kernel replaced, kept as a reference for
+// benchmarks and tests
+pub fn gemm_naive<T: Element>(
+    m: usize,
+    n: usize,
+    k: usize,
+    a: Operand<T>,
+    b: Operand<T>,
+    c: &mut [T],
+) {
+    assert!(
+        c.len() >= m * n,
+        "Result buffer is too small for a {m}x{n} product"
+    );
+    if n == 0 {
+        return;
+    }
+
+    c[..m * n]
+        .par_chunks_mut(n)
+        .enumerate()
+        .for_each(|(i, row)| {
+            for (j, val) in row.iter_mut().enumerate() {
+                let mut sum = T::zero();
+
+                for p in 0..k {
+                    sum += a.get(i, p) * b.get(p, j);
+                }
+
+                *val += sum;
+            }
+        });
+}
+
+// i-k-j order so the innermost loop walks rows of `b` and `c`
+fn gemm_small<T: Element>(m: usize, n: usize, k: usize, a: Operand<T>, b: Operand<T>, c: &mut [T]) {
+    for i in 0..m {
+        let c_row = &mut c[i * n..(i + 1) * n];
+
+        for p in 0..k {
+            let a_ip = a.get(i, p);
+            for (j, val) in c_row.iter_mut().enumerate() {
+                *val += a_ip * b.get(p, j);
+            }
+        }
+    }
+}
+
+// Packs rows p0..p0 + kc of `b` into NR wide column panels, each stored depth first and zero
+// padded past the last column
+fn pack_b<T: Element>(b: Operand<T>, p0: usize, kc: usize, n: usize, packed: &mut [T]) {
+    for jr in (0..n).step_by(NR) {
+        let panel = &mut packed[jr * kc..(jr + NR) * kc];
+
+        for (p, dst) in panel.chunks_exact_mut(NR).enumerate() {
+            for (j, val) in dst.iter_mut().enumerate() {
+                *val = if jr + j < n {
+                    b.get(p0 + p, jr + j)
+                } else {
+                    T::zero()
+                };
+            }
+        }
+    }
+}
+
+// Packs the (mc x kc) block of `a` at (i0, p0) into MR tall row panels, each stored depth first
+// and zero padded past the last row
+fn pack_a<T: Element>(a: Operand<T>, i0: usize, mc: usize, p0: usize, kc: usize, packed: &mut [T]) {
+    for ir in (0..mc).step_by(MR) {
+        let panel = &mut packed[ir * kc..(ir + MR) * kc];
+
+        for (p, dst) in panel.chunks_exact_mut(MR).enumerate() {
+            for (i, val) in dst.iter_mut().enumerate() {
+                *val = if ir + i < mc {
+                    a.get(i0 + ir + i, p0 + p)
+                } else {
+                    T::zero()
+                };
+            }
+        }
+    }
+}
+
+// Fixed size loops over the tile let the compiler vectorize this on any target
+pub(crate) fn kernel_scalar<T: Element>(kc: usize, a: &[T], b: &[T], acc: &mut [[T; NR]; MR]) {
+    for (a, b) in a.chunks_exact(MR).zip(b.chunks_exact(NR)).take(kc) {
+        for (acc_row, a_i) in acc.iter_mut().zip(a) {
+            for (acc, b_j) in acc_row.iter_mut().zip(b) {
+                *acc += *a_i * *b_j;
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn kernel_avx2_fma(kc: usize, a: &[f32], b: &[f32], acc: &mut [[f32; NR]; MR]) {
+    assert!(a.len() >= kc * MR && b.len() >= kc * NR);
+    // SAFETY: only selected once AVX2 and FMA support has been detected at runtime, and the
+    // panels were checked to hold kc steps
+    unsafe { kernel_avx2_fma_unchecked(kc, a, b, acc) }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn kernel_avx2_fma_unchecked(kc: usize, a: &[f32], b: &[f32], acc: &mut [[f32; NR]; MR]) {
+    use std::arch::x86_64::*;
+
+    let mut c = [[_mm256_setzero_ps(); 2]; MR];
+
+    for p in 0..kc {
+        // SAFETY: the caller guarantees b holds kc * NR values and a holds kc * MR values
+        let (b0, b1) = unsafe {
+            let b = b.as_ptr().add(p * NR);
+            (_mm256_loadu_ps(b), _mm256_loadu_ps(b.add(8)))
+        };
+
+        for (i, c_row) in c.iter_mut().enumerate() {
+            let a_i = _mm256_set1_ps(a[p * MR + i]);
+            c_row[0] = _mm256_fmadd_ps(a_i, b0, c_row[0]);
+            c_row[1] = _mm256_fmadd_ps(a_i, b1, c_row[1]);
+        }
+    }
+
+    for (acc_row, c_row) in acc.iter_mut().zip(c) {
+        // SAFETY: each accumulator row holds NR = 16 values
+        unsafe {
+            _mm256_storeu_ps(acc_row.as_mut_ptr(), c_row[0]);
+            _mm256_storeu_ps(acc_row.as_mut_ptr().add(8), c_row[1]);
+        }
+    }
+}
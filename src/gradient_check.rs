@@ -0,0 +1,28 @@
+// Agreement between the backpropagated and finite difference gradients of one layer's
+// parameters, `layer` 0 is the weights and biases feeding the first layer after the inputs
+#[derive(Clone, Debug)]
+pub struct LayerGradientCheck {
+    pub layer: usize,
+    pub weight_error: f64,
+    pub bias_error: f64,
+}
+
+impl LayerGradientCheck {
+    pub fn max_error(&self) -> f64 {
+        self.weight_error.max(self.bias_error)
+    }
+}
+
+// |analytic - numerical| / (|analytic| + |numerical|) over the whole gradient, 0 when both vanish
+pub fn relative_error(analytic: &[f64], numerical: &[f64]) -> f64 {
+    let norm = |values: &mut dyn Iterator<Item = f64>| values.map(|x| x * x).sum::<f64>().sqrt();
+
+    let difference = norm(&mut analytic.iter().zip(numerical).map(|(a, n)| a - n));
+    let magnitude = norm(&mut analytic.iter().copied()) + norm(&mut numerical.iter().copied());
+
+    if magnitude == 0.0 {
+        0.0
+    } else {
+        difference / magnitude
+    }
+}
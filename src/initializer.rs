@@ -0,0 +1,159 @@
+use std::f64::consts::PI;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{activation::ActivationFunction, element::Element, matrix::Matrix};
+
+// Fan-in is the number of columns of a weight matrix and fan-out the number of rows
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Initializer {
+    Zeros,
+    Constant(f32),
+    Uniform { low: f32, high: f32 },
+    Normal { mean: f32, std_dev: f32 },
+    XavierUniform,
+    XavierNormal,
+    HeUniform,
+    HeNormal,
+    LecunUniform,
+    LecunNormal,
+    Orthogonal { gain: f32 },
+}
+
+impl Initializer {
+    // The usual pairing for an activation, He for the ReLU family, LeCun for SELU and Xavier otherwise
+    pub fn default_for(activation: ActivationFunction) -> Initializer {
+        match activation {
+            ActivationFunction::Relu
+            | ActivationFunction::LeakyRelu { .. }
+            | ActivationFunction::Elu { .. }
+            | ActivationFunction::Gelu
+            | ActivationFunction::Swish => Initializer::HeNormal,
+            ActivationFunction::Selu => Initializer::LecunNormal,
+            _ => Initializer::XavierUniform,
+        }
+    }
+
+    // Values are drawn in f64 and rounded to the element type
+    pub fn initialize<T: Element, R: Rng + ?Sized>(
+        &self,
+        rows: usize,
+        cols: usize,
+        rng: &mut R,
+    ) -> Matrix<T> {
+        let fan_in = cols as f64;
+        let fan_out = rows as f64;
+
+        match self {
+            Initializer::Zeros => Matrix::zeros(rows, cols),
+            Initializer::Constant(value) => Matrix {
+                rows,
+                cols,
+                data: vec![T::from_f32(*value); rows * cols],
+            },
+            Initializer::Uniform { low, high } => {
+                uniform(rows, cols, *low as f64, *high as f64, rng)
+            }
+            Initializer::Normal { mean, std_dev } => {
+                normal(rows, cols, *mean as f64, *std_dev as f64, rng)
+            }
+            Initializer::XavierUniform => {
+                let limit = (6.0 / (fan_in + fan_out)).sqrt();
+                uniform(rows, cols, -limit, limit, rng)
+            }
+            Initializer::XavierNormal => {
+                normal(rows, cols, 0.0, (2.0 / (fan_in + fan_out)).sqrt(), rng)
+            }
+            Initializer::HeUniform => {
+                let limit = (6.0 / fan_in).sqrt();
+                uniform(rows, cols, -limit, limit, rng)
+            }
+            Initializer::HeNormal => normal(rows, cols, 0.0, (2.0 / fan_in).sqrt(), rng),
+            Initializer::LecunUniform => {
+                let limit = (3.0 / fan_in).sqrt();
+                uniform(rows, cols, -limit, limit, rng)
+            }
+            Initializer::LecunNormal => normal(rows, cols, 0.0, (1.0 / fan_in).sqrt(), rng),
+            Initializer::Orthogonal { gain } => orthogonal(rows, cols, *gain as f64, rng),
+        }
+    }
+}
+
+fn uniform<T: Element, R: Rng + ?Sized>(
+    rows: usize,
+    cols: usize,
+    low: f64,
+    high: f64,
+    rng: &mut R,
+) -> Matrix<T> {
+    let data = (0..rows * cols)
+        .map(|_| T::from_f64(low + rng.random::<f64>() * (high - low)))
+        .collect();
+
+    Matrix { rows, cols, data }
+}
+
+fn normal<T: Element, R: Rng + ?Sized>(
+    rows: usize,
+    cols: usize,
+    mean: f64,
+    std_dev: f64,
+    rng: &mut R,
+) -> Matrix<T> {
+    let data = (0..rows * cols)
+        .map(|_| T::from_f64(mean + std_dev * standard_normal(rng)))
+        .collect();
+
+    Matrix { rows, cols, data }
+}
+
+// Box-Muller transform
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    // Shift into (0, 1] so the logarithm stays finite
+    let u1 = 1.0 - rng.random::<f64>();
+    let u2 = rng.random::<f64>();
+
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+// Orthonormalizes random gaussian vectors along the shorter dimension with Gram-Schmidt
+fn orthogonal<T: Element, R: Rng + ?Sized>(
+    rows: usize,
+    cols: usize,
+    gain: f64,
+    rng: &mut R,
+) -> Matrix<T> {
+    let count = rows.min(cols);
+    let length = rows.max(cols);
+    let mut vectors: Vec<Vec<f64>> = Vec::with_capacity(count);
+
+    while vectors.len() < count {
+        let mut vector: Vec<f64> = (0..length).map(|_| standard_normal(rng)).collect();
+
+        for previous in &vectors {
+            let projection: f64 = vector.iter().zip(previous).map(|(a, b)| a * b).sum();
+            vector
+                .iter_mut()
+                .zip(previous)
+                .for_each(|(a, b)| *a -= projection * b);
+        }
+
+        let norm = vector.iter().map(|x| x * x).sum::<f64>().sqrt();
+        // Retry on the vanishingly unlikely draw that is linearly dependent on the others
+        if norm > 1e-6 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+            vectors.push(vector);
+        }
+    }
+
+    let mut matrix = Matrix::zeros(rows, cols);
+    for (k, vector) in vectors.iter().enumerate() {
+        for (l, val) in vector.iter().enumerate() {
+            let (i, j) = if rows <= cols { (k, l) } else { (l, k) };
+            matrix.data[i * cols + j] = T::from_f64(gain * val);
+        }
+    }
+
+    matrix
+}
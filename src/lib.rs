@@ -0,0 +1,26 @@
+pub mod activation;
+pub mod builder;
+pub mod callback;
+mod checkpoint;
+pub mod datasets;
+pub mod element;
+pub mod error;
+pub mod gemm;
+pub mod gradient_check;
+pub mod initializer;
+pub mod loss;
+pub mod matrix;
+pub mod metrics;
+pub mod network;
+pub mod optimizer;
+pub mod report;
+pub mod schedule;
+pub mod training;
+
+pub use activation::ActivationFunction;
+pub use builder::NetworkBuilder;
+pub use element::{Element, ElementType};
+pub use error::{Error, Result};
+pub use loss::Loss;
+pub use matrix::{Matrix, MatrixView};
+pub use network::Network;
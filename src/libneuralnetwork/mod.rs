@@ -1,3 +0,0 @@
-pub mod activation;
-pub mod matrix;
-pub mod network;
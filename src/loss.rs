@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{element::Element, matrix::Matrix};
+
+// Keeps logarithms finite when an output saturates at 0 or 1
+const EPSILON: f64 = 1e-7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Loss {
+    MeanSquaredError,
+    MeanAbsoluteError,
+    Huber { delta: f32 },
+    BinaryCrossEntropy,
+    // Categorical cross-entropy, paired with a softmax output layer the output error is
+    // computed directly as `a - y`
+    CrossEntropy,
+}
+
+impl Loss {
+    // Average loss per sample, each column of `outputs` and `expected` is one sample
+    pub fn value<T: Element>(&self, outputs: &Matrix<T>, expected: &Matrix<T>) -> T {
+        assert_same_shape(outputs, expected);
+
+        let total: T = outputs
+            .data
+            .iter()
+            .zip(&expected.data)
+            .map(|(a, y)| self.element_value(*a, *y))
+            .sum();
+
+        total / T::from_f64(outputs.cols as f64)
+    }
+
+    // Gradient of the loss with respect to the outputs of the network
+    pub fn gradient<T: Element>(&self, outputs: &Matrix<T>, expected: &Matrix<T>) -> Matrix<T> {
+        let mut gradient = Matrix::zeros(0, 0);
+        self.gradient_into(outputs, expected, &mut gradient);
+        gradient
+    }
+
+    // Writes the gradient into `gradient`, reusing its allocation
+    pub fn gradient_into<T: Element>(
+        &self,
+        outputs: &Matrix<T>,
+        expected: &Matrix<T>,
+        gradient: &mut Matrix<T>,
+    ) {
+        assert_same_shape(outputs, expected);
+        gradient.reset(outputs.rows, outputs.cols);
+
+        for ((gradient, a), y) in gradient
+            .data
+            .iter_mut()
+            .zip(&outputs.data)
+            .zip(&expected.data)
+        {
+            *gradient = self.element_gradient(*a, *y);
+        }
+    }
+
+    fn element_value<T: Element>(&self, a: T, y: T) -> T {
+        let one = T::one();
+        let epsilon = T::from_f64(EPSILON);
+
+        match self {
+            Loss::MeanSquaredError => (a - y).powi(2),
+            Loss::MeanAbsoluteError => (a - y).abs(),
+            Loss::Huber { delta } => {
+                let delta = T::from_f32(*delta);
+                let half = T::from_f64(0.5);
+                let difference = (a - y).abs();
+                if difference <= delta {
+                    half * difference.powi(2)
+                } else {
+                    delta * (difference - half * delta)
+                }
+            }
+            Loss::BinaryCrossEntropy => {
+                let a = a.max(epsilon).min(one - epsilon);
+                -(y * a.ln() + (one - y) * (one - a).ln())
+            }
+            Loss::CrossEntropy => -y * a.max(epsilon).ln(),
+        }
+    }
+
+    fn element_gradient<T: Element>(&self, a: T, y: T) -> T {
+        let zero = T::zero();
+        let one = T::one();
+        let epsilon = T::from_f64(EPSILON);
+
+        match self {
+            Loss::MeanSquaredError => T::from_f64(2.0) * (a - y),
+            Loss::MeanAbsoluteError => {
+                if a == y {
+                    zero
+                } else {
+                    (a - y).signum()
+                }
+            }
+            Loss::Huber { delta } => {
+                let delta = T::from_f32(*delta);
+                (a - y).max(-delta).min(delta)
+            }
+            Loss::BinaryCrossEntropy => {
+                let a = a.max(epsilon).min(one - epsilon);
+                (a - y) / (a * (one - a))
+            }
+            Loss::CrossEntropy => -y / a.max(epsilon),
+        }
+    }
+}
+
+fn assert_same_shape<T>(outputs: &Matrix<T>, expected: &Matrix<T>) {
+    assert!(
+        outputs.rows == expected.rows && outputs.cols == expected.cols,
+        "Attempt to compute loss of outputs of size {}x{} against expected outputs of size {}x{}",
+        outputs.rows,
+        outputs.cols,
+        expected.rows,
+        expected.cols
+    );
+}
@@ -0,0 +1,553 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Debug,
+    ops::{Add, AddAssign, Index, Mul, MulAssign, Sub, SubAssign},
+};
+
+use crate::{
+    element::Element,
+    error::{Error, Result},
+    gemm::{Operand, gemm},
+};
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Matrix<T = f32> {
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<T>,
+}
+
+// A borrowed, possibly transposed, look at a matrix that can be multiplied without copying
+#[derive(Clone, Copy)]
+pub struct MatrixView<'a, T = f32> {
+    pub rows: usize,
+    pub cols: usize,
+    operand: Operand<'a, T>,
+}
+
+impl<T: Element> Matrix<T> {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix {
+            rows,
+            cols,
+            data: vec![T::zero(); rows * cols],
+        }
+    }
+
+    // Reshapes to (rows x cols) filled with zeros, reusing the existing allocation when it is
+    // large enough
+    pub fn reset(&mut self, rows: usize, cols: usize) {
+        self.rows = rows;
+        self.cols = cols;
+        self.data.clear();
+        self.data.resize(rows * cols, T::zero());
+    }
+
+    // Overwrites this matrix with a copy of `source` without reallocating when possible
+    pub fn copy_from(&mut self, source: &Matrix<T>) {
+        self.rows = source.rows;
+        self.cols = source.cols;
+        self.data.clone_from(&source.data);
+    }
+
+    pub fn dot(&self, rhs: &Matrix<T>) -> Matrix<T> {
+        self.try_dot(rhs).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_dot(&self, rhs: &Matrix<T>) -> Result<Matrix<T>> {
+        let mut product = self.clone();
+        product.try_hadamard_inplace(rhs)?;
+        Ok(product)
+    }
+
+    // Elementwise product stored in self
+    pub fn hadamard_inplace(&mut self, rhs: &Matrix<T>) {
+        self.try_hadamard_inplace(rhs)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_hadamard_inplace(&mut self, rhs: &Matrix<T>) -> Result<()> {
+        check_same_shape("dot multiply", self, rhs)?;
+
+        for (lhs, rhs) in self.data.iter_mut().zip(&rhs.data) {
+            *lhs *= *rhs;
+        }
+
+        Ok(())
+    }
+
+    // self += alpha * x, the BLAS axpy
+    pub fn scaled_add(&mut self, alpha: T, x: &Matrix<T>) {
+        self.try_scaled_add(alpha, x)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_scaled_add(&mut self, alpha: T, x: &Matrix<T>) -> Result<()> {
+        check_same_shape("add", self, x)?;
+
+        for (lhs, x) in self.data.iter_mut().zip(&x.data) {
+            *lhs += alpha * *x;
+        }
+
+        Ok(())
+    }
+
+    pub fn try_add(&self, rhs: &Matrix<T>) -> Result<Matrix<T>> {
+        matrix_add(self, rhs)
+    }
+
+    pub fn try_sub(&self, rhs: &Matrix<T>) -> Result<Matrix<T>> {
+        matrix_subtract(self, rhs)
+    }
+
+    pub fn try_mul(&self, rhs: &Matrix<T>) -> Result<Matrix<T>> {
+        matrix_multiply(self, rhs)
+    }
+
+    // Writes self * rhs into `out`, which is reshaped as needed so it can be reused between calls
+    pub fn matmul_into(&self, rhs: &Matrix<T>, out: &mut Matrix<T>) {
+        self.try_matmul_into(rhs, out)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_matmul_into(&self, rhs: &Matrix<T>, out: &mut Matrix<T>) -> Result<()> {
+        self.view().try_matmul_into(rhs.view(), out)
+    }
+
+    // self^T * rhs without materializing the transpose
+    pub fn matmul_tn(&self, rhs: &Matrix<T>) -> Matrix<T> {
+        self.try_matmul_tn(rhs)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_matmul_tn(&self, rhs: &Matrix<T>) -> Result<Matrix<T>> {
+        self.view().transpose().try_matmul(rhs.view())
+    }
+
+    // self * rhs^T without materializing the transpose
+    pub fn matmul_nt(&self, rhs: &Matrix<T>) -> Matrix<T> {
+        self.try_matmul_nt(rhs)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_matmul_nt(&self, rhs: &Matrix<T>) -> Result<Matrix<T>> {
+        self.view().try_matmul(rhs.view().transpose())
+    }
+
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView {
+            rows: self.rows,
+            cols: self.cols,
+            operand: Operand::row_major(&self.data, self.cols),
+        }
+    }
+
+    pub fn transpose(&self) -> Matrix<T> {
+        let mut transpose: Matrix<T> = Matrix::zeros(self.cols, self.rows);
+
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                transpose.data[j * transpose.cols + i] = self.data[i * self.cols + j];
+            }
+        }
+
+        transpose
+    }
+
+    // pub fn map(self, function: Box<dyn Fn(f64) -> f64>) -> Matrix<T> {
+    pub fn map(&self, function: impl Fn(T) -> T) -> Matrix<T> {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|val| function(*val)).collect(),
+        }
+    }
+
+    // Sums down each column, giving a (1 x cols) row
+    pub fn sum_rows(&self) -> Matrix<T> {
+        let mut sums = Matrix::zeros(1, self.cols);
+
+        for row in self.data.chunks(self.cols.max(1)) {
+            for (sum, val) in sums.data.iter_mut().zip(row) {
+                *sum += *val;
+            }
+        }
+
+        sums
+    }
+
+    // Sums along each row, giving a (rows x 1) column, e.g. collapsing a batch of samples
+    pub fn sum_cols(&self) -> Matrix<T> {
+        let mut sums = Matrix::zeros(0, 0);
+        self.sum_cols_into(&mut sums);
+        sums
+    }
+
+    pub fn sum_cols_into(&self, sums: &mut Matrix<T>) {
+        sums.reset(self.rows, 1);
+
+        for (sum, row) in sums.data.iter_mut().zip(self.data.chunks(self.cols.max(1))) {
+            *sum = row.iter().copied().sum();
+        }
+    }
+
+    // Mean of every element, NaN for an empty matrix
+    pub fn mean(&self) -> T {
+        self.data.iter().copied().sum::<T>() / T::from_f64(self.data.len() as f64)
+    }
+
+    // Largest element, negative infinity for an empty matrix
+    pub fn max(&self) -> T {
+        self.data.iter().copied().fold(T::neg_infinity(), T::max)
+    }
+
+    // Row index of the largest element of each column, the first one on ties
+    pub fn argmax_per_col(&self) -> Vec<usize> {
+        (0..self.cols).map(|j| self.argmax_col(j)).collect()
+    }
+
+    // Row of the largest element in column `j`, the first on ties
+    pub fn argmax_col(&self, j: usize) -> usize {
+        (0..self.rows).fold(0, |best, i| {
+            if self.data[i * self.cols + j] > self.data[best * self.cols + j] {
+                i
+            } else {
+                best
+            }
+        })
+    }
+
+    // Adds a (rows x 1) column to every column in place, e.g. a bias to a batch
+    pub fn broadcast_add_col(&mut self, column: &Matrix<T>) {
+        self.try_broadcast_add_col(column)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_broadcast_add_col(&mut self, column: &Matrix<T>) -> Result<()> {
+        if column.rows != self.rows || column.cols != 1 {
+            return Err(broadcast_mismatch(self, column));
+        }
+
+        for (row, val) in self.data.chunks_mut(self.cols.max(1)).zip(&column.data) {
+            row.iter_mut().for_each(|x| *x += *val);
+        }
+
+        Ok(())
+    }
+
+    // Adds a (1 x cols) row to every row in place
+    pub fn broadcast_add_row(&mut self, row: &Matrix<T>) {
+        self.try_broadcast_add_row(row)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_broadcast_add_row(&mut self, row: &Matrix<T>) -> Result<()> {
+        if row.rows != 1 || row.cols != self.cols {
+            return Err(broadcast_mismatch(self, row));
+        }
+
+        for self_row in self.data.chunks_mut(self.cols.max(1)) {
+            for (x, val) in self_row.iter_mut().zip(&row.data) {
+                *x += *val;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Frobenius norm, the square root of the sum of squares
+    pub fn norm(&self) -> T {
+        self.data.iter().map(|x| *x * *x).sum::<T>().sqrt()
+    }
+
+    pub fn clip(&self, min: T, max: T) -> Matrix<T> {
+        self.map(|x| x.max(min).min(max))
+    }
+
+    pub fn clip_inplace(&mut self, min: T, max: T) {
+        self.map_inplace(|x| x.max(min).min(max));
+    }
+
+    // Converts every element to another element type, rounding if it is narrower
+    pub fn cast<U: Element>(&self) -> Matrix<U> {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .map(|val| U::from_f64(val.as_f64()))
+                .collect(),
+        }
+    }
+
+    pub fn map_inplace(&mut self, function: impl Fn(T) -> T) {
+        self.data.iter_mut().for_each(|val| *val = function(*val));
+    }
+}
+
+impl<T: Element> MatrixView<'_, T> {
+    pub fn transpose(self) -> Self {
+        MatrixView {
+            rows: self.cols,
+            cols: self.rows,
+            operand: self.operand.transpose(),
+        }
+    }
+
+    pub fn try_matmul(self, rhs: MatrixView<T>) -> Result<Matrix<T>> {
+        let mut product: Matrix<T> = Matrix::zeros(0, 0);
+        self.try_matmul_into(rhs, &mut product)?;
+        Ok(product)
+    }
+
+    pub fn matmul_into(self, rhs: MatrixView<T>, out: &mut Matrix<T>) {
+        self.try_matmul_into(rhs, out)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_matmul_into(self, rhs: MatrixView<T>, out: &mut Matrix<T>) -> Result<()> {
+        if self.cols != rhs.rows {
+            return Err(Error::ShapeMismatch {
+                operation: "multiply",
+                lhs: (self.rows, self.cols),
+                rhs: (rhs.rows, rhs.cols),
+            });
+        }
+
+        out.reset(self.rows, rhs.cols);
+
+        gemm(
+            self.rows,
+            rhs.cols,
+            self.cols,
+            self.operand,
+            rhs.operand,
+            &mut out.data,
+        );
+
+        Ok(())
+    }
+}
+
+impl<T: Element> From<&Vec<Vec<T>>> for Matrix<T> {
+    fn from(data: &Vec<Vec<T>>) -> Self {
+        let flat_data = data.iter().flatten().copied().collect::<Vec<T>>();
+
+        Self {
+            rows: data.len(),
+            cols: data[0].len(),
+            data: flat_data,
+        }
+    }
+}
+
+impl<T: Element> From<&Vec<T>> for Matrix<T> {
+    fn from(data: &Vec<T>) -> Self {
+        Self {
+            rows: 1,
+            cols: data.len(),
+            data: data.to_vec(),
+        }
+    }
+}
+
+impl<T: Element> From<Vec<Vec<T>>> for Matrix<T> {
+    fn from(data: Vec<Vec<T>>) -> Self {
+        let flat_data = data.iter().flatten().copied().collect::<Vec<T>>();
+
+        Self {
+            rows: data.len(),
+            cols: data[0].len(),
+            data: flat_data,
+        }
+    }
+}
+
+impl<T: Element> From<Vec<T>> for Matrix<T> {
+    fn from(data: Vec<T>) -> Self {
+        Self {
+            rows: 1,
+            cols: data.len(),
+            data,
+        }
+    }
+}
+
+fn check_same_shape<T: Element>(
+    operation: &'static str,
+    lhs: &Matrix<T>,
+    rhs: &Matrix<T>,
+) -> Result<()> {
+    if lhs.rows == rhs.rows && lhs.cols == rhs.cols {
+        Ok(())
+    } else {
+        Err(Error::ShapeMismatch {
+            operation,
+            lhs: (lhs.rows, lhs.cols),
+            rhs: (rhs.rows, rhs.cols),
+        })
+    }
+}
+
+fn broadcast_mismatch<T>(lhs: &Matrix<T>, rhs: &Matrix<T>) -> Error {
+    Error::ShapeMismatch {
+        operation: "broadcast add",
+        lhs: (lhs.rows, lhs.cols),
+        rhs: (rhs.rows, rhs.cols),
+    }
+}
+
+// Matrix addition
+fn matrix_add<T: Element>(lhs: &Matrix<T>, rhs: &Matrix<T>) -> Result<Matrix<T>> {
+    check_same_shape("add", lhs, rhs)?;
+
+    let mut sum: Matrix<T> = Matrix::zeros(lhs.rows, lhs.cols);
+
+    for i in 0..lhs.rows {
+        for j in 0..lhs.cols {
+            sum.data[i * lhs.cols + j] = lhs.data[i * lhs.cols + j] + rhs.data[i * lhs.cols + j];
+        }
+    }
+
+    Ok(sum)
+}
+
+impl<T: Element> Add<&Matrix<T>> for Matrix<T> {
+    type Output = Self;
+
+    fn add(self, rhs: &Matrix<T>) -> Self::Output {
+        matrix_add(&self, rhs).unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+impl<T: Element> Add<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, rhs: &Matrix<T>) -> Self::Output {
+        matrix_add(self, rhs).unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+impl<T: Element> AddAssign<&Matrix<T>> for Matrix<T> {
+    fn add_assign(&mut self, rhs: &Matrix<T>) {
+        self.scaled_add(T::one(), rhs);
+    }
+}
+
+// Matrix subtraction
+fn matrix_subtract<T: Element>(lhs: &Matrix<T>, rhs: &Matrix<T>) -> Result<Matrix<T>> {
+    check_same_shape("subtract", lhs, rhs)?;
+
+    let mut difference: Matrix<T> = Matrix::zeros(lhs.rows, lhs.cols);
+
+    for i in 0..lhs.rows {
+        for j in 0..lhs.cols {
+            difference.data[i * lhs.cols + j] =
+                lhs.data[i * lhs.cols + j] - rhs.data[i * lhs.cols + j];
+        }
+    }
+
+    Ok(difference)
+}
+
+impl<T: Element> Sub<&Matrix<T>> for Matrix<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: &Matrix<T>) -> Self::Output {
+        matrix_subtract(&self, rhs).unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+impl<T: Element> Sub<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(self, rhs: &Matrix<T>) -> Self::Output {
+        matrix_subtract(self, rhs).unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+impl<T: Element> SubAssign<&Matrix<T>> for Matrix<T> {
+    fn sub_assign(&mut self, rhs: &Matrix<T>) {
+        check_same_shape("subtract", self, rhs).unwrap_or_else(|error| panic!("{error}"));
+
+        for (lhs, rhs) in self.data.iter_mut().zip(&rhs.data) {
+            *lhs -= *rhs;
+        }
+    }
+}
+
+// Matrix multiplication
+fn matrix_multiply<T: Element>(lhs: &Matrix<T>, rhs: &Matrix<T>) -> Result<Matrix<T>> {
+    lhs.view().try_matmul(rhs.view())
+}
+
+impl<T: Element> Mul<&Matrix<T>> for Matrix<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: &Matrix<T>) -> Self::Output {
+        matrix_multiply(&self, rhs).unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+impl<T: Element> Mul<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &Matrix<T>) -> Self::Output {
+        matrix_multiply(self, rhs).unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+// Scalar multiplication
+fn matrix_scalar_multiplication<T: Element>(lhs: &Matrix<T>, rhs: T) -> Matrix<T> {
+    let mut product: Matrix<T> = Matrix::zeros(lhs.rows, lhs.cols);
+
+    for i in 0..lhs.rows {
+        for j in 0..lhs.cols {
+            product.data[i * lhs.cols + j] = rhs * lhs.data[i * lhs.cols + j];
+        }
+    }
+
+    product
+}
+
+impl<T: Element> Mul<T> for Matrix<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        matrix_scalar_multiplication(&self, rhs)
+    }
+}
+
+impl<T: Element> Mul<T> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        matrix_scalar_multiplication(self, rhs)
+    }
+}
+
+impl<T: Element> MulAssign<T> for Matrix<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        self.map_inplace(|val| val * rhs);
+    }
+}
+
+impl<T: Element> Index<usize> for Matrix<T> {
+    type Output = [T];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        let linear_index = index * self.cols;
+        &self.data[linear_index..linear_index + self.cols]
+    }
+}
+
+impl<T: Element> Debug for Matrix<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Matrix:").unwrap();
+        for i in 0..self.data.len() {
+            writeln!(f, "\t{:?}", self.data[i]).unwrap();
+        }
+        Ok(())
+    }
+}
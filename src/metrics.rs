@@ -0,0 +1,186 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    callback::{BatchStats, Callback, Control},
+    element::Element,
+    network::Network,
+    training::EpochStats,
+};
+
+// One row of the training log, written at the end of every epoch
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EpochRecord {
+    pub epoch: u16,
+    // Mini batches trained on since training started, counting those before a resume
+    pub step: u64,
+    pub train_loss: f32,
+    pub val_loss: Option<f32>,
+    pub accuracy: f32,
+    pub val_accuracy: Option<f32>,
+    pub learning_rate: f32,
+    // Seconds spent training since training started, counting the time before a resume
+    pub wall_time: f64,
+    pub samples_per_second: f64,
+}
+
+// Destination for epoch records
+pub trait MetricSink {
+    fn record(&mut self, record: &EpochRecord) -> io::Result<()>;
+}
+
+impl<S: MetricSink + ?Sized> MetricSink for Box<S> {
+    fn record(&mut self, record: &EpochRecord) -> io::Result<()> {
+        (**self).record(record)
+    }
+}
+
+// Writes a header and then one comma separated row per record, leaving missing values empty
+pub struct CsvSink<W: Write> {
+    writer: W,
+    wrote_header: bool,
+}
+
+impl CsvSink<BufWriter<File>> {
+    pub fn create(path: &str) -> io::Result<Self> {
+        Ok(CsvSink::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> CsvSink<W> {
+    pub fn new(writer: W) -> Self {
+        CsvSink {
+            writer,
+            wrote_header: false,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> MetricSink for CsvSink<W> {
+    fn record(&mut self, record: &EpochRecord) -> io::Result<()> {
+        if !self.wrote_header {
+            writeln!(
+                self.writer,
+                "epoch,step,train_loss,val_loss,accuracy,val_accuracy,learning_rate,wall_time,samples_per_second"
+            )?;
+            self.wrote_header = true;
+        }
+
+        let optional =
+            |value: Option<f32>| value.map(|value| value.to_string()).unwrap_or_default();
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{},{},{},{}",
+            record.epoch,
+            record.step,
+            record.train_loss,
+            optional(record.val_loss),
+            record.accuracy,
+            optional(record.val_accuracy),
+            record.learning_rate,
+            record.wall_time,
+            record.samples_per_second
+        )?;
+        self.writer.flush()
+    }
+}
+
+// Writes every record as a JSON object on its own line, with missing values as null
+pub struct JsonLinesSink<W: Write> {
+    writer: W,
+}
+
+impl JsonLinesSink<BufWriter<File>> {
+    pub fn create(path: &str) -> io::Result<Self> {
+        Ok(JsonLinesSink::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        JsonLinesSink { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> MetricSink for JsonLinesSink<W> {
+    fn record(&mut self, record: &EpochRecord) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        writeln!(self.writer)?;
+        self.writer.flush()
+    }
+}
+
+// Callback turning the stats of every epoch into records for `sink`. A failed write stops training,
+// the error is kept for `error`
+pub struct MetricsLogger<S: MetricSink> {
+    sink: S,
+    step: u64,
+    epoch_samples: usize,
+    error: Option<io::Error>,
+}
+
+impl<S: MetricSink> MetricsLogger<S> {
+    pub fn new(sink: S) -> Self {
+        MetricsLogger {
+            sink,
+            step: 0,
+            epoch_samples: 0,
+            error: None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+}
+
+impl<T: Element, S: MetricSink> Callback<T> for MetricsLogger<S> {
+    fn on_epoch_start(&mut self, _epoch: u16, _epochs: u16) -> Control {
+        self.epoch_samples = 0;
+        Control::Continue
+    }
+
+    fn on_batch_end(&mut self, _network: &Network<T>, batch: &BatchStats) -> Control {
+        self.step = batch.step;
+        self.epoch_samples += batch.samples;
+        Control::Continue
+    }
+
+    fn on_epoch_end(&mut self, _network: &Network<T>, stats: &EpochStats) -> Control {
+        let record = EpochRecord {
+            epoch: stats.epoch,
+            step: self.step,
+            train_loss: stats.loss,
+            val_loss: stats.validation_loss,
+            accuracy: stats.accuracy,
+            val_accuracy: stats.validation_accuracy,
+            learning_rate: stats.learning_rate,
+            wall_time: stats.elapsed.as_secs_f64(),
+            samples_per_second: self.epoch_samples as f64 / stats.duration.as_secs_f64(),
+        };
+
+        match self.sink.record(&record) {
+            Ok(()) => Control::Continue,
+            Err(error) => {
+                self.error = Some(error);
+                Control::Stop
+            }
+        }
+    }
+}
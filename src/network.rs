@@ -0,0 +1,1167 @@
+use rand::{Rng, SeedableRng, seq::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, IgnoredAny, SeqAccess, Visitor},
+};
+use std::{
+    fmt,
+    fs::{File, read},
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    activation::ActivationFunction,
+    builder::NetworkBuilder,
+    callback::{self, BatchStats, Callback, Control},
+    checkpoint::{self, TrainingState},
+    element::{Element, ElementType},
+    error::{Error, Result},
+    gradient_check::{LayerGradientCheck, relative_error},
+    initializer::Initializer,
+    loss::Loss,
+    matrix::Matrix,
+    optimizer::{Optimizer, OptimizerKind},
+    report::EvaluationReport,
+    schedule::Scheduler,
+    training::{CheckpointInterval, EpochStats, Evaluation, TrainingConfig, class_of},
+};
+
+// Samples fed through at once when measuring accuracy
+const EVALUATION_BATCH_SIZE: usize = 256;
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "T: Element")]
+pub struct Network<T = f32> {
+    // Written first so it can be read without decoding the rest of a saved network
+    element_type: ElementType,
+    layers: Vec<usize>,
+    weights: Vec<Matrix<T>>,
+    biases: Vec<Matrix<T>>,
+    activations: Vec<ActivationFunction>,
+    loss: Loss,
+    learning_rate: f32,
+    optimizer: OptimizerKind<T>,
+    // Seed the weights were initialized from, also the default seed for shuffling during training
+    seed: u64,
+    #[serde(skip)]
+    z_history: Vec<Matrix<T>>,
+    #[serde(skip)]
+    activation_history: Vec<Matrix<T>>,
+    #[serde(skip)]
+    workspace: Workspace<T>,
+}
+
+// Buffers reused between training steps, once they have grown to the batch size a step no
+// longer allocates
+#[derive(Default)]
+struct Workspace<T> {
+    loss_gradient: Matrix<T>,
+    upstream: Matrix<T>,
+    errors: Vec<Matrix<T>>,
+    nabla_w: Vec<Matrix<T>>,
+    nabla_b: Vec<Matrix<T>>,
+}
+
+impl<T: Element> Network<T> {
+    pub fn builder(input_size: usize) -> NetworkBuilder<T> {
+        NetworkBuilder::new(input_size)
+    }
+
+    // `layers` lists the size and activation of every layer after the input layer, weights use
+    // the default initializer for their activation and biases start at zero. Without a seed a
+    // random one is drawn, either way it is recorded so the run can be reproduced
+    pub fn new(
+        input_size: usize,
+        layers: Vec<(usize, ActivationFunction)>,
+        loss: Loss,
+        learning_rate: f32,
+        optimizer: OptimizerKind<T>,
+        seed: Option<u64>,
+    ) -> Network<T> {
+        let layers = layers
+            .into_iter()
+            .map(|(size, activation)| (size, activation, Initializer::default_for(activation)))
+            .collect();
+
+        Self::with_initializers(
+            input_size,
+            layers,
+            Initializer::Zeros,
+            loss,
+            learning_rate,
+            optimizer,
+            seed,
+        )
+    }
+
+    pub fn with_initializers(
+        input_size: usize,
+        layers: Vec<(usize, ActivationFunction, Initializer)>,
+        bias_initializer: Initializer,
+        loss: Loss,
+        learning_rate: f32,
+        optimizer: OptimizerKind<T>,
+        seed: Option<u64>,
+    ) -> Network<T> {
+        assert!(!layers.is_empty(), "Network must have at least one layer");
+
+        let seed = seed.unwrap_or_else(|| rand::rng().random());
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let activations: Vec<ActivationFunction> = layers
+            .iter()
+            .map(|(_, activation, _)| *activation)
+            .collect();
+        let initializers: Vec<Initializer> = layers
+            .iter()
+            .map(|(_, _, initializer)| *initializer)
+            .collect();
+        let layers: Vec<usize> = std::iter::once(input_size)
+            .chain(layers.iter().map(|(size, _, _)| *size))
+            .collect();
+
+        let mut weights: Vec<Matrix<T>> = Vec::new();
+        let mut biases: Vec<Matrix<T>> = Vec::new();
+
+        for i in 0..layers.len() - 1 {
+            weights.push(initializers[i].initialize(layers[i + 1], layers[i], &mut rng));
+            biases.push(bias_initializer.initialize(layers[i + 1], 1, &mut rng));
+        }
+
+        Network {
+            element_type: T::ELEMENT_TYPE,
+            layers,
+            weights,
+            biases,
+            activations,
+            loss,
+            learning_rate,
+            optimizer,
+            seed,
+            z_history: Vec::new(),
+            activation_history: Vec::new(),
+            workspace: Workspace::default(),
+        }
+    }
+
+    pub fn from_file<P: AsRef<str>>(filename: P) -> Result<Self> {
+        let buf = read(filename.as_ref())?;
+        let network: Network<T> = rmp_serde::from_slice(&buf)?;
+
+        if network.element_type != T::ELEMENT_TYPE {
+            return Err(Error::ElementTypeMismatch {
+                expected: T::ELEMENT_TYPE,
+                found: network.element_type,
+            });
+        }
+
+        network.check_structure()?;
+        Ok(network)
+    }
+
+    // Every weight matrix has to map its layer to the next and every bias to be a column of the
+    // next layer's size, anything else would panic part way through a pass
+    pub(crate) fn check_structure(&self) -> Result<()> {
+        if self.layers.len() < 2 {
+            return Err(Error::CorruptNetwork(format!(
+                "{} layers, a network needs at least an input and an output layer",
+                self.layers.len()
+            )));
+        }
+
+        let layer_count = self.layers.len() - 1;
+        for (name, count) in [
+            ("weight matrices", self.weights.len()),
+            ("bias vectors", self.biases.len()),
+            ("activations", self.activations.len()),
+        ] {
+            if count != layer_count {
+                return Err(Error::CorruptNetwork(format!(
+                    "{count} {name} for {layer_count} layers"
+                )));
+            }
+        }
+
+        check_parameter_shapes(&self.layers, &self.weights, &self.biases)
+    }
+
+    pub fn feed_forward(&self, inputs: &Vec<T>) -> Vec<T> {
+        self.try_feed_forward(inputs)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_feed_forward(&self, inputs: &Vec<T>) -> Result<Vec<T>> {
+        Ok(self
+            .try_feed_forward_batch(&Self::batch_matrix(&[inputs]))?
+            .data)
+    }
+
+    // Feeds a (features x batch) matrix through the network, giving a (outputs x batch) matrix
+    pub fn feed_forward_batch(&self, inputs: &Matrix<T>) -> Matrix<T> {
+        self.try_feed_forward_batch(inputs)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_feed_forward_batch(&self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
+        let mut activation = inputs.clone();
+
+        for i in 0..self.layers.len() - 1 {
+            let mut z = self.weights[i].try_mul(&activation)?;
+            z.try_broadcast_add_col(&self.biases[i])?;
+            activation = self.activate(i, &z);
+        }
+
+        Ok(activation)
+    }
+
+    // Records every layer's weighted inputs and activations in the history buffers, the
+    // outputs are then available from `recorded_outputs`
+    fn feed_forward_and_record(&mut self, inputs: &Matrix<T>) {
+        assert!(
+            inputs.rows == self.layers[0],
+            "Number of inputs does not match number of neurons in the first layer"
+        );
+
+        let layer_count = self.layers.len() - 1;
+        self.z_history.resize_with(layer_count, Matrix::default);
+        self.activation_history
+            .resize_with(layer_count + 1, Matrix::default);
+        self.activation_history[0].copy_from(inputs);
+
+        for i in 0..layer_count {
+            let z = &mut self.z_history[i];
+            self.weights[i].matmul_into(&self.activation_history[i], z);
+            z.broadcast_add_col(&self.biases[i]);
+            self.activations[i].activate_into(z, &mut self.activation_history[i + 1]);
+        }
+    }
+
+    fn recorded_outputs(&self) -> &Matrix<T> {
+        &self.activation_history[self.activation_history.len() - 1]
+    }
+
+    // Gradients averaged over the batch, each column of the recorded outputs is one sample. The
+    // results are left in the workspace's `nabla_w` and `nabla_b`
+    fn back_propogate(&mut self, expected_outputs: &Matrix<T>) {
+        assert!(
+            expected_outputs.rows == *self.layers.last().unwrap(),
+            "Number of expected outputs does not match number of neurons in the last layer"
+        );
+
+        let fused_softmax = self.is_fused_softmax();
+        let layer_count = self.layers.len() - 1;
+        let outputs = &self.activation_history[layer_count];
+        let scale = T::one() / T::from_f64(outputs.cols as f64);
+
+        let workspace = &mut self.workspace;
+        workspace.errors.resize_with(layer_count, Matrix::default);
+        workspace.nabla_w.resize_with(layer_count, Matrix::default);
+        workspace.nabla_b.resize_with(layer_count, Matrix::default);
+
+        let l = layer_count - 1;
+        if fused_softmax {
+            workspace.errors[l].copy_from(outputs);
+            workspace.errors[l] -= expected_outputs;
+        } else {
+            self.loss
+                .gradient_into(outputs, expected_outputs, &mut workspace.loss_gradient);
+            self.activations[l].backward_into(
+                &self.z_history[l],
+                outputs,
+                &workspace.loss_gradient,
+                &mut workspace.errors[l],
+            );
+        }
+
+        for l in (0..layer_count).rev() {
+            if l < layer_count - 1 {
+                self.weights[l + 1]
+                    .view()
+                    .transpose()
+                    .matmul_into(workspace.errors[l + 1].view(), &mut workspace.upstream);
+                self.activations[l].backward_into(
+                    &self.z_history[l],
+                    &self.activation_history[l + 1],
+                    &workspace.upstream,
+                    &mut workspace.errors[l],
+                );
+            }
+
+            let error = &workspace.errors[l];
+            error.view().matmul_into(
+                self.activation_history[l].view().transpose(),
+                &mut workspace.nabla_w[l],
+            );
+            workspace.nabla_w[l] *= scale;
+            error.sum_cols_into(&mut workspace.nabla_b[l]);
+            workspace.nabla_b[l] *= scale;
+        }
+    }
+
+    // Steps the optimizer with the gradients left in the workspace by `back_propogate`
+    fn apply_gradients(&mut self, learning_rate: f32) {
+        let workspace = std::mem::take(&mut self.workspace);
+        self.step_optimizer(&workspace.nabla_w, &workspace.nabla_b, learning_rate);
+        self.workspace = workspace;
+    }
+
+    // Compares the backpropagated gradients over a batch with central finite differences of the
+    // loss, perturbing every weight and bias by +-epsilon in turn
+    pub fn gradient_check(
+        &mut self,
+        inputs: &[Vec<T>],
+        expected_outputs: &[Vec<T>],
+        epsilon: T,
+    ) -> Result<Vec<LayerGradientCheck>> {
+        self.check_samples(inputs, expected_outputs)?;
+        // An empty batch has no gradient to compare
+        Self::check_not_empty(inputs)?;
+
+        let indices: Vec<usize> = (0..inputs.len()).collect();
+        let mut input_batch = Matrix::zeros(0, 0);
+        let mut expected_batch = Matrix::zeros(0, 0);
+        Self::fill_batch(inputs, &indices, &mut input_batch);
+        Self::fill_batch(expected_outputs, &indices, &mut expected_batch);
+
+        self.feed_forward_and_record(&input_batch);
+        self.back_propogate(&expected_batch);
+        let workspace = std::mem::take(&mut self.workspace);
+
+        let report = (0..self.weights.len())
+            .map(|layer| {
+                let analytic_w: Vec<f64> = workspace.nabla_w[layer]
+                    .data
+                    .iter()
+                    .map(|val| val.as_f64())
+                    .collect();
+                let analytic_b: Vec<f64> = workspace.nabla_b[layer]
+                    .data
+                    .iter()
+                    .map(|val| val.as_f64())
+                    .collect();
+                let numerical_w =
+                    self.numerical_gradient(layer, false, &input_batch, &expected_batch, epsilon);
+                let numerical_b =
+                    self.numerical_gradient(layer, true, &input_batch, &expected_batch, epsilon);
+
+                LayerGradientCheck {
+                    layer,
+                    weight_error: relative_error(&analytic_w, &numerical_w),
+                    bias_error: relative_error(&analytic_b, &numerical_b),
+                }
+            })
+            .collect();
+
+        self.workspace = workspace;
+        Ok(report)
+    }
+
+    fn numerical_gradient(
+        &mut self,
+        layer: usize,
+        bias: bool,
+        inputs: &Matrix<T>,
+        expected_outputs: &Matrix<T>,
+        epsilon: T,
+    ) -> Vec<f64> {
+        let len = self.parameter_mut(layer, bias).data.len();
+
+        (0..len)
+            .map(|i| {
+                let original = self.parameter_mut(layer, bias).data[i];
+                let (plus, minus) = (original + epsilon, original - epsilon);
+
+                self.parameter_mut(layer, bias).data[i] = plus;
+                let plus_loss = self.batch_loss(inputs, expected_outputs);
+                self.parameter_mut(layer, bias).data[i] = minus;
+                let minus_loss = self.batch_loss(inputs, expected_outputs);
+                self.parameter_mut(layer, bias).data[i] = original;
+
+                // Divide by the step actually taken, which rounding can make differ from 2 * epsilon
+                (plus_loss - minus_loss) / (plus - minus).as_f64()
+            })
+            .collect()
+    }
+
+    fn parameter_mut(&mut self, layer: usize, bias: bool) -> &mut Matrix<T> {
+        if bias {
+            &mut self.biases[layer]
+        } else {
+            &mut self.weights[layer]
+        }
+    }
+
+    fn batch_loss(&self, inputs: &Matrix<T>, expected_outputs: &Matrix<T>) -> f64 {
+        self.loss
+            .value(&self.feed_forward_batch(inputs), expected_outputs)
+            .as_f64()
+    }
+
+    pub fn update_network(&mut self, nabla_w: &[Matrix<T>], nabla_b: &[Matrix<T>]) {
+        self.try_update_network(nabla_w, nabla_b)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    // Gradients have to come one per layer in the shape of the parameters they update
+    pub fn try_update_network(
+        &mut self,
+        nabla_w: &[Matrix<T>],
+        nabla_b: &[Matrix<T>],
+    ) -> Result<()> {
+        for (parameters, gradients) in [(&self.weights, nabla_w), (&self.biases, nabla_b)] {
+            if gradients.len() != parameters.len() {
+                return Err(Error::LengthMismatch {
+                    expected: parameters.len(),
+                    found: gradients.len(),
+                });
+            }
+
+            for (parameter, gradient) in parameters.iter().zip(gradients) {
+                if (gradient.rows, gradient.cols) != (parameter.rows, parameter.cols)
+                    || gradient.data.len() != gradient.rows * gradient.cols
+                {
+                    return Err(Error::ShapeMismatch {
+                        operation: "update",
+                        lhs: (parameter.rows, parameter.cols),
+                        rhs: (gradient.rows, gradient.cols),
+                    });
+                }
+            }
+        }
+
+        self.step_optimizer(nabla_w, nabla_b, self.learning_rate);
+        Ok(())
+    }
+
+    fn step_optimizer(&mut self, nabla_w: &[Matrix<T>], nabla_b: &[Matrix<T>], learning_rate: f32) {
+        let layer_count = self.layers.len() - 1;
+        self.optimizer.begin_step();
+
+        for i in 0..layer_count {
+            self.optimizer
+                .step(i, &mut self.weights[i], &nabla_w[i], learning_rate);
+            self.optimizer.step(
+                layer_count + i,
+                &mut self.biases[i],
+                &nabla_b[i],
+                learning_rate,
+            );
+        }
+    }
+
+    // Per sample gradient descent over the samples in order, without shuffling
+    pub fn train(
+        &mut self,
+        training_inputs: &[Vec<T>],
+        training_outputs: &[Vec<T>],
+        epochs: u16,
+        evaluation: Evaluation,
+        callbacks: &mut [&mut dyn Callback<T>],
+    ) -> Result<Vec<EpochStats>> {
+        self.check_samples(training_inputs, training_outputs)?;
+
+        let mut epoch_stats: Vec<EpochStats> = Vec::new();
+        let mut inputs = Matrix::zeros(0, 0);
+        let mut expected_outputs = Matrix::zeros(0, 0);
+        let training_start = Instant::now();
+
+        'epochs: for i in 1..=epochs {
+            let start_time = Instant::now();
+            if callback::dispatch(callbacks, |c| c.on_epoch_start(i, epochs)) == Control::Stop {
+                break;
+            }
+
+            let mut total_loss = 0.0;
+            let mut passes = 0;
+
+            for j in 0..training_inputs.len() {
+                Self::fill_batch(training_inputs, &[j], &mut inputs);
+                Self::fill_batch(training_outputs, &[j], &mut expected_outputs);
+
+                self.feed_forward_and_record(&inputs);
+                let loss = self
+                    .loss
+                    .value(self.recorded_outputs(), &expected_outputs)
+                    .as_f32();
+                total_loss += loss;
+                passes += evaluation.passes(self.recorded_outputs(), &expected_outputs);
+                self.back_propogate(&expected_outputs);
+                self.apply_gradients(self.learning_rate);
+
+                let batch = BatchStats {
+                    epoch: i,
+                    batch: j,
+                    step: ((i - 1) as usize * training_inputs.len() + j + 1) as u64,
+                    samples: 1,
+                    loss,
+                    learning_rate: self.learning_rate,
+                };
+                if callback::dispatch(callbacks, |c| c.on_batch_end(self, &batch)) == Control::Stop
+                {
+                    break 'epochs;
+                }
+            }
+
+            let stats = EpochStats {
+                epoch: i,
+                loss: total_loss / training_inputs.len() as f32,
+                accuracy: passes as f32 / training_inputs.len() as f32,
+                validation_loss: None,
+                validation_accuracy: None,
+                learning_rate: self.learning_rate,
+                duration: start_time.elapsed(),
+                elapsed: training_start.elapsed(),
+            };
+            let control = callback::dispatch(callbacks, |c| c.on_epoch_end(self, &stats));
+            epoch_stats.push(stats);
+
+            if control == Control::Stop {
+                break;
+            }
+        }
+
+        callback::dispatch(callbacks, |c| {
+            c.on_train_end(self, &epoch_stats);
+            Control::Continue
+        });
+
+        Ok(epoch_stats)
+    }
+
+    pub fn stochastic_train(
+        &mut self,
+        training_inputs: &[Vec<T>],
+        training_outputs: &[Vec<T>],
+        config: &TrainingConfig,
+    ) -> Vec<EpochStats> {
+        self.try_stochastic_train(training_inputs, training_outputs, config)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_stochastic_train(
+        &mut self,
+        training_inputs: &[Vec<T>],
+        training_outputs: &[Vec<T>],
+        config: &TrainingConfig,
+    ) -> Result<Vec<EpochStats>> {
+        self.try_stochastic_train_with(training_inputs, training_outputs, config, &mut [])
+    }
+
+    pub fn stochastic_train_with(
+        &mut self,
+        training_inputs: &[Vec<T>],
+        training_outputs: &[Vec<T>],
+        config: &TrainingConfig,
+        callbacks: &mut [&mut dyn Callback<T>],
+    ) -> Vec<EpochStats> {
+        self.try_stochastic_train_with(training_inputs, training_outputs, config, callbacks)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    // Mini batch training calling `callbacks` through the loop, any of which can end it early
+    pub fn try_stochastic_train_with(
+        &mut self,
+        training_inputs: &[Vec<T>],
+        training_outputs: &[Vec<T>],
+        config: &TrainingConfig,
+        callbacks: &mut [&mut dyn Callback<T>],
+    ) -> Result<Vec<EpochStats>> {
+        config.validate()?;
+
+        let epochs = config.epochs;
+        let mut state = match &config.resume_from {
+            Some(path) => {
+                let (network, state) = checkpoint::load(path)?;
+                if network.layers != self.layers {
+                    return Err(Error::ArchitectureMismatch {
+                        expected: self.layers.clone(),
+                        found: network.layers,
+                    });
+                }
+                let samples = state.order.len() + state.validation.len();
+                if samples != training_inputs.len() {
+                    return Err(Error::LengthMismatch {
+                        expected: samples,
+                        found: training_inputs.len(),
+                    });
+                }
+                state.check_resumable(config, config.seed.unwrap_or(network.seed))?;
+
+                *self = network;
+                state
+            }
+            None => self.start_training(training_inputs.len(), config),
+        };
+        // After resuming, so the samples are checked against the network that trains on them
+        self.check_samples(training_inputs, training_outputs)?;
+        // A split can round to none or to every sample when there are only a few
+        if config.early_stopping.is_some() && state.validation.is_empty() {
+            return Err(Error::InvalidConfig(format!(
+                "Early stopping needs a validation split holding out at least one of {} samples",
+                training_inputs.len()
+            )));
+        }
+        if state.order.is_empty() {
+            return Err(Error::InvalidConfig(format!(
+                "No samples are left to train on out of {} after the validation split",
+                training_inputs.len()
+            )));
+        }
+        if config.drop_last && state.order.len() < config.mini_batch_size {
+            return Err(Error::InvalidConfig(format!(
+                "Dropping the last mini batch leaves nothing to train on, {} samples are fewer \
+                 than a mini batch of {}",
+                state.order.len(),
+                config.mini_batch_size
+            )));
+        }
+
+        let batch_count = state.order.len().div_ceil(config.mini_batch_size);
+        let mut inputs = Matrix::zeros(0, 0);
+        let mut labels = Matrix::zeros(0, 0);
+        // Training time carries on from the checkpoint when resuming
+        let (training_start, elapsed_before) = (Instant::now(), state.elapsed);
+
+        'epochs: while state.epoch < epochs {
+            let i = state.epoch + 1;
+            let start_time = Instant::now();
+            if callback::dispatch(callbacks, |c| c.on_epoch_start(i, epochs)) == Control::Stop {
+                break;
+            }
+
+            // A checkpoint from the middle of an epoch has already shuffled it
+            if state.batch == 0 {
+                state.order.shuffle(&mut state.rng);
+            }
+
+            while state.batch < batch_count {
+                let start = state.batch * config.mini_batch_size;
+                let end = (start + config.mini_batch_size).min(state.order.len());
+                let batch_size = end - start;
+                state.batch += 1;
+
+                if config.drop_last && batch_size < config.mini_batch_size {
+                    continue;
+                }
+
+                Self::fill_batch(training_inputs, &state.order[start..end], &mut inputs);
+                Self::fill_batch(training_outputs, &state.order[start..end], &mut labels);
+
+                // Forward and backward pass over the whole mini batch at once
+                self.feed_forward_and_record(&inputs);
+                let outputs = self.recorded_outputs();
+                let batch = BatchStats {
+                    epoch: i,
+                    batch: state.batch - 1,
+                    step: state.scheduler.steps() + 1,
+                    samples: batch_size,
+                    loss: self.loss.value(outputs, &labels).as_f32(),
+                    learning_rate: state.scheduler.learning_rate(),
+                };
+                state.total_loss += batch.loss * batch_size as f32;
+                state.passes += config.evaluation.passes(outputs, &labels);
+                state.sample_count += batch_size;
+
+                state.learning_rate = batch.learning_rate;
+                self.back_propogate(&labels);
+                self.apply_gradients(state.learning_rate);
+                state.scheduler.step();
+
+                if let Some(checkpointing) = &config.checkpoint
+                    && let CheckpointInterval::Steps(steps) = checkpointing.every
+                    && state.scheduler.steps() % steps.max(1) == 0
+                {
+                    state.elapsed = elapsed_before + training_start.elapsed();
+                    checkpoint::save(&checkpointing.path, self, &state)?;
+                }
+
+                if callback::dispatch(callbacks, |c| c.on_batch_end(self, &batch)) == Control::Stop
+                {
+                    break 'epochs;
+                }
+            }
+
+            let (validation_loss, validation_accuracy) = if state.validation.is_empty() {
+                (None, None)
+            } else {
+                let (loss, passes) = self.measure(
+                    training_inputs,
+                    training_outputs,
+                    &state.validation,
+                    config.evaluation,
+                )?;
+                let count = state.validation.len() as f32;
+                (Some(loss as f32 / count), Some(passes as f32 / count))
+            };
+
+            let learning_rate = state.learning_rate;
+            let stats = EpochStats {
+                epoch: i,
+                loss: state.total_loss / state.sample_count as f32,
+                accuracy: state.passes as f32 / state.sample_count as f32,
+                validation_loss,
+                validation_accuracy,
+                learning_rate,
+                duration: start_time.elapsed(),
+                elapsed: elapsed_before + training_start.elapsed(),
+            };
+            state
+                .scheduler
+                .end_epoch(validation_loss.unwrap_or(stats.loss));
+
+            let mut stop =
+                callback::dispatch(callbacks, |c| c.on_epoch_end(self, &stats)) == Control::Stop;
+
+            if let Some(early_stopping) = &config.early_stopping {
+                let value = stats.monitored(early_stopping.monitor).unwrap();
+
+                if state
+                    .best
+                    .is_none_or(|best| early_stopping.improves(value, best))
+                {
+                    state.best = Some(value);
+                    if early_stopping.restore_best_weights {
+                        state.best_parameters = Some((self.weights.clone(), self.biases.clone()));
+                    }
+                    state.epochs_without_improvement = 0;
+                } else {
+                    state.epochs_without_improvement += 1;
+                    stop |= state.epochs_without_improvement >= early_stopping.patience;
+                }
+            }
+
+            state.epoch_stats.push(stats);
+            state.epoch = i;
+            state.batch = 0;
+            state.total_loss = 0.0;
+            state.passes = 0;
+            state.sample_count = 0;
+
+            if let Some(checkpointing) = &config.checkpoint
+                && let CheckpointInterval::Epochs(epochs) = checkpointing.every
+                && i % epochs.max(1) == 0
+            {
+                state.elapsed = elapsed_before + training_start.elapsed();
+                checkpoint::save(&checkpointing.path, self, &state)?;
+            }
+
+            if stop {
+                break;
+            }
+        }
+
+        if let Some((weights, biases)) = state.best_parameters {
+            self.weights = weights;
+            self.biases = biases;
+        }
+
+        callback::dispatch(callbacks, |c| {
+            c.on_train_end(self, &state.epoch_stats);
+            Control::Continue
+        });
+
+        Ok(state.epoch_stats)
+    }
+
+    // Fresh training state, holding out a random subset of the samples for validation once, every
+    // epoch then only shuffles the rest
+    fn start_training(&self, sample_count: usize, config: &TrainingConfig) -> TrainingState<T> {
+        let seed = config.seed.unwrap_or(self.seed);
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut order: Vec<usize> = (0..sample_count).collect();
+
+        let mut validation = Vec::new();
+        if config.validation_split > 0.0 {
+            order.shuffle(&mut rng);
+            let validation_size = (order.len() as f32 * config.validation_split).round() as usize;
+            validation = order.split_off(order.len() - validation_size);
+        }
+
+        let batches_per_epoch = if config.drop_last {
+            order.len() / config.mini_batch_size
+        } else {
+            order.len().div_ceil(config.mini_batch_size)
+        };
+        let scheduler = Scheduler::new(
+            config.lr_schedule.clone(),
+            self.learning_rate,
+            (batches_per_epoch * config.epochs as usize) as u64,
+        );
+
+        TrainingState {
+            rng,
+            order,
+            validation,
+            learning_rate: scheduler.learning_rate(),
+            scheduler,
+            epoch: 0,
+            batch: 0,
+            total_loss: 0.0,
+            passes: 0,
+            sample_count: 0,
+            best: None,
+            best_parameters: None,
+            epochs_without_improvement: 0,
+            epoch_stats: Vec::new(),
+            elapsed: Duration::ZERO,
+            mini_batch_size: config.mini_batch_size,
+            drop_last: config.drop_last,
+            validation_split: config.validation_split,
+            lr_schedule: config.lr_schedule.clone(),
+            seed,
+        }
+    }
+
+    // Fraction of samples evaluated as correct, writing every result to `output`
+    pub fn test<W: Write>(
+        &self,
+        inputs_set: &[Vec<T>],
+        expected_outputs_set: &[Vec<T>],
+        evaluation: Evaluation,
+        output: &mut W,
+    ) -> Result<f32> {
+        self.check_samples(inputs_set, expected_outputs_set)?;
+        Self::check_not_empty(inputs_set)?;
+        let mut passes = 0;
+
+        for (i, (inputs, label)) in inputs_set.iter().zip(expected_outputs_set).enumerate() {
+            let results = self.try_feed_forward(inputs)?;
+            // Each sample as a (n x 1) column
+            let results = Matrix::from(results).transpose();
+            let label_column = Matrix::from(label).transpose();
+            let passed = evaluation.passes(&results, &label_column) == 1;
+
+            output.write_all(format!("Test {} of {}\n", i, inputs_set.len()).as_bytes())?;
+            output.write_all(format!("Results : {:?}\n", results.data).as_bytes())?;
+            output.write_all(format!("Expected: {label:?}\n").as_bytes())?;
+            output.write_all(format!("Passed  : {passed}\n").as_bytes())?;
+
+            if passed {
+                passes += 1;
+            }
+        }
+
+        Ok(passes as f32 / inputs_set.len() as f32)
+    }
+
+    // Fraction of samples evaluated as correct, feeding them through in batches
+    pub fn accuracy(
+        &self,
+        inputs_set: &[Vec<T>],
+        expected_outputs_set: &[Vec<T>],
+        evaluation: Evaluation,
+    ) -> Result<f32> {
+        self.check_samples(inputs_set, expected_outputs_set)?;
+        Self::check_not_empty(inputs_set)?;
+        let indices: Vec<usize> = (0..inputs_set.len()).collect();
+        let (_, passes) = self.measure(inputs_set, expected_outputs_set, &indices, evaluation)?;
+
+        Ok(passes as f32 / inputs_set.len() as f32)
+    }
+
+    // Summed loss and number of passes over the samples at `indices`, fed through in batches
+    fn measure(
+        &self,
+        inputs_set: &[Vec<T>],
+        expected_outputs_set: &[Vec<T>],
+        indices: &[usize],
+        evaluation: Evaluation,
+    ) -> Result<(f64, usize)> {
+        let mut inputs = Matrix::zeros(0, 0);
+        let mut labels = Matrix::zeros(0, 0);
+        let mut total_loss = 0.0;
+        let mut passes = 0;
+
+        for batch in indices.chunks(EVALUATION_BATCH_SIZE) {
+            Self::fill_batch(inputs_set, batch, &mut inputs);
+            Self::fill_batch(expected_outputs_set, batch, &mut labels);
+            let outputs = self.try_feed_forward_batch(&inputs)?;
+            total_loss += self.loss.value(&outputs, &labels).as_f64() * batch.len() as f64;
+            passes += evaluation.passes(&outputs, &labels);
+        }
+
+        Ok((total_loss, passes))
+    }
+
+    // Confusion matrix, per class metrics, top-k accuracy and average loss over labelled samples,
+    // the class of each sample is the index of its largest expected output
+    pub fn evaluate(
+        &self,
+        inputs_set: &[Vec<T>],
+        expected_outputs_set: &[Vec<T>],
+        top_k: usize,
+    ) -> Result<EvaluationReport> {
+        self.check_samples(inputs_set, expected_outputs_set)?;
+        let output_size = *self.layers.last().unwrap();
+        // A single output tells two classes apart
+        let class_count = output_size.max(2);
+        let indices: Vec<usize> = (0..inputs_set.len()).collect();
+        let mut confusion_matrix = vec![vec![0; class_count]; class_count];
+        let mut top_k_hits = 0;
+        let mut total_loss = 0.0;
+        let mut inputs = Matrix::zeros(0, 0);
+        let mut labels = Matrix::zeros(0, 0);
+
+        for batch in indices.chunks(EVALUATION_BATCH_SIZE) {
+            Self::fill_batch(inputs_set, batch, &mut inputs);
+            Self::fill_batch(expected_outputs_set, batch, &mut labels);
+            let outputs = self.try_feed_forward_batch(&inputs)?;
+            total_loss += self.loss.value(&outputs, &labels).as_f64() * batch.len() as f64;
+
+            for j in 0..batch.len() {
+                let (label, prediction) = (class_of(&labels, j), class_of(&outputs, j));
+                confusion_matrix[label][prediction] += 1;
+
+                // Rank of the label among the outputs, ties go to the lower index like the
+                // prediction so top-1 matches accuracy. With a single output only the prediction
+                // is ranked first
+                let rank = if output_size == 1 {
+                    (prediction != label) as usize
+                } else {
+                    let label_output = outputs.data[label * outputs.cols + j];
+                    (0..outputs.rows)
+                        .filter(|&i| {
+                            let output = outputs.data[i * outputs.cols + j];
+                            output > label_output || (i < label && output == label_output)
+                        })
+                        .count()
+                };
+                if rank < top_k {
+                    top_k_hits += 1;
+                }
+            }
+        }
+
+        Ok(EvaluationReport::from_counts(
+            confusion_matrix,
+            top_k,
+            top_k_hits,
+            total_loss,
+        ))
+    }
+
+    // Checks that every sample has an expected output and both match the shape of the network
+    fn check_samples(&self, inputs: &[Vec<T>], expected_outputs: &[Vec<T>]) -> Result<()> {
+        if inputs.len() != expected_outputs.len() {
+            return Err(Error::LengthMismatch {
+                expected: inputs.len(),
+                found: expected_outputs.len(),
+            });
+        }
+
+        let input_size = self.layers[0];
+        let output_size = *self.layers.last().unwrap();
+
+        for (input, expected_output) in inputs.iter().zip(expected_outputs) {
+            if input.len() != input_size {
+                return Err(Error::LengthMismatch {
+                    expected: input_size,
+                    found: input.len(),
+                });
+            }
+
+            if expected_output.len() != output_size {
+                return Err(Error::LengthMismatch {
+                    expected: output_size,
+                    found: expected_output.len(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    // A fraction of no samples would be NaN, at least one is expected
+    fn check_not_empty(inputs: &[Vec<T>]) -> Result<()> {
+        if inputs.is_empty() {
+            return Err(Error::LengthMismatch {
+                expected: 1,
+                found: 0,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Stacks samples as the columns of a (features x batch) matrix
+    fn batch_matrix(samples: &[&Vec<T>]) -> Matrix<T> {
+        let rows = samples[0].len();
+        let cols = samples.len();
+        let mut batch = Matrix::zeros(rows, cols);
+
+        for (j, sample) in samples.iter().enumerate() {
+            for (i, val) in sample.iter().enumerate() {
+                batch.data[i * cols + j] = *val;
+            }
+        }
+
+        batch
+    }
+
+    // Fills `batch` with the samples at `indices` as its columns, reusing its allocation
+    fn fill_batch(samples: &[Vec<T>], indices: &[usize], batch: &mut Matrix<T>) {
+        let rows = samples[indices[0]].len();
+        let cols = indices.len();
+        batch.reset(rows, cols);
+
+        for (j, &index) in indices.iter().enumerate() {
+            for (i, val) in samples[index].iter().enumerate() {
+                batch.data[i * cols + j] = *val;
+            }
+        }
+    }
+
+    pub fn element_type(&self) -> ElementType {
+        self.element_type
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Sizes of every layer, including the input layer
+    pub fn layers(&self) -> &[usize] {
+        &self.layers
+    }
+
+    pub fn activations(&self) -> &[ActivationFunction] {
+        &self.activations
+    }
+
+    // Weights and biases of every layer after the input layer
+    pub fn weights(&self) -> &[Matrix<T>] {
+        &self.weights
+    }
+
+    pub fn biases(&self) -> &[Matrix<T>] {
+        &self.biases
+    }
+
+    pub fn loss_function(&self) -> Loss {
+        self.loss
+    }
+
+    pub fn learning_rate(&self) -> f32 {
+        self.learning_rate
+    }
+
+    pub fn optimizer(&self) -> &OptimizerKind<T> {
+        &self.optimizer
+    }
+
+    pub fn parameter_count(&self) -> usize {
+        self.weights
+            .iter()
+            .chain(&self.biases)
+            .map(|matrix| matrix.data.len())
+            .sum()
+    }
+
+    fn activate(&self, i: usize, z: &Matrix<T>) -> Matrix<T> {
+        self.activations[i].activate(z)
+    }
+
+    // Softmax output with cross-entropy loss has the simplified output error `a - y`
+    fn is_fused_softmax(&self) -> bool {
+        self.loss == Loss::CrossEntropy
+            && self.activations[self.activations.len() - 1] == ActivationFunction::Softmax
+    }
+
+    pub fn loss(&self, inputs: &Vec<T>, expected_outputs: &Vec<T>) -> T {
+        self.try_loss(inputs, expected_outputs)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_loss(&self, inputs: &Vec<T>, expected_outputs: &Vec<T>) -> Result<T> {
+        self.check_samples(
+            std::slice::from_ref(inputs),
+            std::slice::from_ref(expected_outputs),
+        )?;
+
+        let outputs = self.try_feed_forward_batch(&Self::batch_matrix(&[inputs]))?;
+        Ok(self
+            .loss
+            .value(&outputs, &Self::batch_matrix(&[expected_outputs])))
+    }
+
+    pub fn save<P: AsRef<str>>(&self, filename: P) -> Result<()> {
+        let mut file = File::create(filename.as_ref())?;
+        let mut buf = Vec::new();
+        self.serialize(&mut rmp_serde::Serializer::new(&mut buf))?;
+
+        file.write_all(&buf)?;
+        Ok(())
+    }
+}
+
+// Weights and biases, e.g. the best ones kept by early stopping, against the layer sizes
+pub(crate) fn check_parameter_shapes<T: Element>(
+    layers: &[usize],
+    weights: &[Matrix<T>],
+    biases: &[Matrix<T>],
+) -> Result<()> {
+    for (i, (weight, bias)) in weights.iter().zip(biases).enumerate() {
+        let (inputs, outputs) = (layers[i], layers[i + 1]);
+
+        for (name, matrix, shape) in [
+            ("weights", weight, (outputs, inputs)),
+            ("biases", bias, (outputs, 1)),
+        ] {
+            if (matrix.rows, matrix.cols) != shape {
+                return Err(Error::CorruptNetwork(format!(
+                    "layer {i} {name} are {}x{} instead of {}x{}",
+                    matrix.rows, matrix.cols, shape.0, shape.1
+                )));
+            }
+            if matrix.data.len() != matrix.rows * matrix.cols {
+                return Err(Error::CorruptNetwork(format!(
+                    "layer {i} {name} hold {} values for {}x{}",
+                    matrix.data.len(),
+                    matrix.rows,
+                    matrix.cols
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Reads the element type of a saved network, so callers can pick the `Network<T>` to load it as
+pub fn element_type_of_file<P: AsRef<str>>(filename: P) -> Result<ElementType> {
+    let buf = read(filename.as_ref())?;
+    let header: Header = rmp_serde::from_slice(&buf)?;
+    Ok(header.0)
+}
+
+// The leading element type of a serialized network, skipping the remaining fields
+struct Header(ElementType);
+
+impl<'de> Deserialize<'de> for Header {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct HeaderVisitor;
+
+        impl<'de> Visitor<'de> for HeaderVisitor {
+            type Value = Header;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a serialized network")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> std::result::Result<Header, A::Error> {
+                let element_type = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+
+                Ok(Header(element_type))
+            }
+        }
+
+        deserializer.deserialize_seq(HeaderVisitor)
+    }
+}
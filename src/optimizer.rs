@@ -0,0 +1,504 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{element::Element, matrix::Matrix};
+
+pub trait Optimizer<T: Element = f32> {
+    // Called once per update, before any parameter is stepped
+    fn begin_step(&mut self) {}
+
+    // Updates a single parameter in place, `slot` identifies its per-parameter state
+    fn step(
+        &mut self,
+        slot: usize,
+        parameter: &mut Matrix<T>,
+        gradient: &Matrix<T>,
+        learning_rate: f32,
+    );
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "T: Element")]
+pub enum OptimizerKind<T = f32> {
+    Sgd(Sgd<T>),
+    Nesterov(Nesterov<T>),
+    Adam(Adam<T>),
+    AdamW(AdamW<T>),
+    RmsProp(RmsProp<T>),
+    Adagrad(Adagrad<T>),
+}
+
+impl<T: Element> Default for OptimizerKind<T> {
+    fn default() -> Self {
+        OptimizerKind::Sgd(Sgd::default())
+    }
+}
+
+impl<T: Element> OptimizerKind<T> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            OptimizerKind::Sgd(_) => "SGD",
+            OptimizerKind::Nesterov(_) => "Nesterov",
+            OptimizerKind::Adam(_) => "Adam",
+            OptimizerKind::AdamW(_) => "AdamW",
+            OptimizerKind::RmsProp(_) => "RMSProp",
+            OptimizerKind::Adagrad(_) => "Adagrad",
+        }
+    }
+
+    // The same optimizer, with any accumulated state, for another element type
+    pub fn cast<U: Element>(&self) -> OptimizerKind<U> {
+        match self {
+            OptimizerKind::Sgd(optimizer) => OptimizerKind::Sgd(optimizer.cast()),
+            OptimizerKind::Nesterov(optimizer) => OptimizerKind::Nesterov(optimizer.cast()),
+            OptimizerKind::Adam(optimizer) => OptimizerKind::Adam(optimizer.cast()),
+            OptimizerKind::AdamW(optimizer) => OptimizerKind::AdamW(optimizer.cast()),
+            OptimizerKind::RmsProp(optimizer) => OptimizerKind::RmsProp(optimizer.cast()),
+            OptimizerKind::Adagrad(optimizer) => OptimizerKind::Adagrad(optimizer.cast()),
+        }
+    }
+}
+
+impl<T: Element> Optimizer<T> for OptimizerKind<T> {
+    fn begin_step(&mut self) {
+        match self {
+            OptimizerKind::Sgd(optimizer) => optimizer.begin_step(),
+            OptimizerKind::Nesterov(optimizer) => optimizer.begin_step(),
+            OptimizerKind::Adam(optimizer) => optimizer.begin_step(),
+            OptimizerKind::AdamW(optimizer) => optimizer.begin_step(),
+            OptimizerKind::RmsProp(optimizer) => optimizer.begin_step(),
+            OptimizerKind::Adagrad(optimizer) => optimizer.begin_step(),
+        }
+    }
+
+    fn step(
+        &mut self,
+        slot: usize,
+        parameter: &mut Matrix<T>,
+        gradient: &Matrix<T>,
+        learning_rate: f32,
+    ) {
+        match self {
+            OptimizerKind::Sgd(optimizer) => {
+                optimizer.step(slot, parameter, gradient, learning_rate)
+            }
+            OptimizerKind::Nesterov(optimizer) => {
+                optimizer.step(slot, parameter, gradient, learning_rate)
+            }
+            OptimizerKind::Adam(optimizer) => {
+                optimizer.step(slot, parameter, gradient, learning_rate)
+            }
+            OptimizerKind::AdamW(optimizer) => {
+                optimizer.step(slot, parameter, gradient, learning_rate)
+            }
+            OptimizerKind::RmsProp(optimizer) => {
+                optimizer.step(slot, parameter, gradient, learning_rate)
+            }
+            OptimizerKind::Adagrad(optimizer) => {
+                optimizer.step(slot, parameter, gradient, learning_rate)
+            }
+        }
+    }
+}
+
+// Returns the state buffer for `slot`, allocating zeroed buffers shaped like `parameter` on first use
+fn state_buffer<'a, T: Element>(
+    buffers: &'a mut Vec<Matrix<T>>,
+    slot: usize,
+    parameter: &Matrix<T>,
+) -> &'a mut Matrix<T> {
+    if buffers.len() <= slot {
+        buffers.resize(slot + 1, Matrix::zeros(0, 0));
+    }
+
+    if buffers[slot].rows != parameter.rows || buffers[slot].cols != parameter.cols {
+        buffers[slot] = Matrix::zeros(parameter.rows, parameter.cols);
+    }
+
+    &mut buffers[slot]
+}
+
+// Epsilons like 1e-8 round to zero in f16, which would turn a zero gradient into a 0 / 0 update
+fn epsilon<T: Element>(epsilon: f32) -> T {
+    T::from_f32(epsilon).max(T::min_positive_value())
+}
+
+fn cast_state<T: Element, U: Element>(buffers: &[Matrix<T>]) -> Vec<Matrix<U>> {
+    buffers.iter().map(Matrix::cast).collect()
+}
+
+fn assert_same_shape<T>(parameter: &Matrix<T>, gradient: &Matrix<T>) {
+    assert!(
+        parameter.rows == gradient.rows && parameter.cols == gradient.cols,
+        "Attempt to step parameter of size {}x{} with gradient of size {}x{}",
+        parameter.rows,
+        parameter.cols,
+        gradient.rows,
+        gradient.cols
+    );
+}
+
+// Gradient descent with classical momentum, a momentum of 0 is vanilla gradient descent
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, bound = "T: Element")]
+pub struct Sgd<T = f32> {
+    pub momentum: f32,
+    velocity: Vec<Matrix<T>>,
+}
+
+impl<T: Element> Sgd<T> {
+    pub fn new(momentum: f32) -> Self {
+        Sgd {
+            momentum,
+            velocity: Vec::new(),
+        }
+    }
+
+    pub fn cast<U: Element>(&self) -> Sgd<U> {
+        Sgd {
+            momentum: self.momentum,
+            velocity: cast_state(&self.velocity),
+        }
+    }
+}
+
+impl<T: Element> Optimizer<T> for Sgd<T> {
+    fn step(
+        &mut self,
+        slot: usize,
+        parameter: &mut Matrix<T>,
+        gradient: &Matrix<T>,
+        learning_rate: f32,
+    ) {
+        assert_same_shape(parameter, gradient);
+
+        let learning_rate = T::from_f32(learning_rate);
+        if self.momentum == 0.0 {
+            parameter.scaled_add(-learning_rate, gradient);
+            return;
+        }
+
+        let momentum = T::from_f32(self.momentum);
+        let velocity = state_buffer(&mut self.velocity, slot, parameter);
+        for ((p, v), g) in parameter
+            .data
+            .iter_mut()
+            .zip(velocity.data.iter_mut())
+            .zip(&gradient.data)
+        {
+            *v = momentum * *v + *g;
+            *p -= learning_rate * *v;
+        }
+    }
+}
+
+// Nesterov accelerated gradient
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, bound = "T: Element")]
+pub struct Nesterov<T = f32> {
+    pub momentum: f32,
+    velocity: Vec<Matrix<T>>,
+}
+
+impl<T: Element> Nesterov<T> {
+    pub fn new(momentum: f32) -> Self {
+        Nesterov {
+            momentum,
+            velocity: Vec::new(),
+        }
+    }
+
+    pub fn cast<U: Element>(&self) -> Nesterov<U> {
+        Nesterov {
+            momentum: self.momentum,
+            velocity: cast_state(&self.velocity),
+        }
+    }
+}
+
+impl<T: Element> Default for Nesterov<T> {
+    fn default() -> Self {
+        Nesterov::new(0.9)
+    }
+}
+
+impl<T: Element> Optimizer<T> for Nesterov<T> {
+    fn step(
+        &mut self,
+        slot: usize,
+        parameter: &mut Matrix<T>,
+        gradient: &Matrix<T>,
+        learning_rate: f32,
+    ) {
+        assert_same_shape(parameter, gradient);
+
+        let (learning_rate, momentum) = (T::from_f32(learning_rate), T::from_f32(self.momentum));
+        let velocity = state_buffer(&mut self.velocity, slot, parameter);
+        for ((p, v), g) in parameter
+            .data
+            .iter_mut()
+            .zip(velocity.data.iter_mut())
+            .zip(&gradient.data)
+        {
+            *v = momentum * *v + *g;
+            *p -= learning_rate * (*g + momentum * *v);
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, bound = "T: Element")]
+pub struct Adam<T = f32> {
+    pub beta1: f32,
+    pub beta2: f32,
+    pub epsilon: f32,
+    timestep: i32,
+    first_moment: Vec<Matrix<T>>,
+    second_moment: Vec<Matrix<T>>,
+}
+
+impl<T: Element> Adam<T> {
+    pub fn new(beta1: f32, beta2: f32, epsilon: f32) -> Self {
+        Adam {
+            beta1,
+            beta2,
+            epsilon,
+            timestep: 0,
+            first_moment: Vec::new(),
+            second_moment: Vec::new(),
+        }
+    }
+
+    pub fn cast<U: Element>(&self) -> Adam<U> {
+        Adam {
+            beta1: self.beta1,
+            beta2: self.beta2,
+            epsilon: self.epsilon,
+            timestep: self.timestep,
+            first_moment: cast_state(&self.first_moment),
+            second_moment: cast_state(&self.second_moment),
+        }
+    }
+
+    // Applies the bias corrected Adam update, shared with AdamW
+    fn adam_step(
+        &mut self,
+        slot: usize,
+        parameter: &mut Matrix<T>,
+        gradient: &Matrix<T>,
+        learning_rate: f32,
+    ) {
+        assert_same_shape(parameter, gradient);
+
+        let one = T::one();
+        let learning_rate = T::from_f32(learning_rate);
+        let (beta1, beta2, epsilon) = (
+            T::from_f32(self.beta1),
+            T::from_f32(self.beta2),
+            epsilon(self.epsilon),
+        );
+        let first_correction = one - beta1.powi(self.timestep);
+        let second_correction = one - beta2.powi(self.timestep);
+
+        state_buffer(&mut self.first_moment, slot, parameter);
+        state_buffer(&mut self.second_moment, slot, parameter);
+        let first_moment = &mut self.first_moment[slot];
+        let second_moment = &mut self.second_moment[slot];
+
+        for (((p, m), v), g) in parameter
+            .data
+            .iter_mut()
+            .zip(first_moment.data.iter_mut())
+            .zip(second_moment.data.iter_mut())
+            .zip(&gradient.data)
+        {
+            *m = beta1 * *m + (one - beta1) * *g;
+            *v = beta2 * *v + (one - beta2) * *g * *g;
+
+            let m_hat = *m / first_correction;
+            let v_hat = *v / second_correction;
+            *p -= learning_rate * m_hat / (v_hat.sqrt() + epsilon);
+        }
+    }
+}
+
+impl<T: Element> Default for Adam<T> {
+    fn default() -> Self {
+        Adam::new(0.9, 0.999, 1e-8)
+    }
+}
+
+impl<T: Element> Optimizer<T> for Adam<T> {
+    fn begin_step(&mut self) {
+        self.timestep += 1;
+    }
+
+    fn step(
+        &mut self,
+        slot: usize,
+        parameter: &mut Matrix<T>,
+        gradient: &Matrix<T>,
+        learning_rate: f32,
+    ) {
+        self.adam_step(slot, parameter, gradient, learning_rate);
+    }
+}
+
+// Adam with decoupled weight decay. Every parameter is decayed, biases included, as the optimizer
+// only sees slots and not what they hold
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, bound = "T: Element")]
+pub struct AdamW<T = f32> {
+    pub weight_decay: f32,
+    adam: Adam<T>,
+}
+
+impl<T: Element> AdamW<T> {
+    pub fn new(beta1: f32, beta2: f32, epsilon: f32, weight_decay: f32) -> Self {
+        AdamW {
+            weight_decay,
+            adam: Adam::new(beta1, beta2, epsilon),
+        }
+    }
+
+    pub fn cast<U: Element>(&self) -> AdamW<U> {
+        AdamW {
+            weight_decay: self.weight_decay,
+            adam: self.adam.cast(),
+        }
+    }
+}
+
+impl<T: Element> Default for AdamW<T> {
+    fn default() -> Self {
+        AdamW::new(0.9, 0.999, 1e-8, 0.01)
+    }
+}
+
+impl<T: Element> Optimizer<T> for AdamW<T> {
+    fn begin_step(&mut self) {
+        self.adam.begin_step();
+    }
+
+    fn step(
+        &mut self,
+        slot: usize,
+        parameter: &mut Matrix<T>,
+        gradient: &Matrix<T>,
+        learning_rate: f32,
+    ) {
+        *parameter *= T::from_f32(1.0 - learning_rate * self.weight_decay);
+
+        self.adam
+            .adam_step(slot, parameter, gradient, learning_rate);
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, bound = "T: Element")]
+pub struct RmsProp<T = f32> {
+    pub decay: f32,
+    pub epsilon: f32,
+    mean_square: Vec<Matrix<T>>,
+}
+
+impl<T: Element> RmsProp<T> {
+    pub fn new(decay: f32, epsilon: f32) -> Self {
+        RmsProp {
+            decay,
+            epsilon,
+            mean_square: Vec::new(),
+        }
+    }
+
+    pub fn cast<U: Element>(&self) -> RmsProp<U> {
+        RmsProp {
+            decay: self.decay,
+            epsilon: self.epsilon,
+            mean_square: cast_state(&self.mean_square),
+        }
+    }
+}
+
+impl<T: Element> Default for RmsProp<T> {
+    fn default() -> Self {
+        RmsProp::new(0.9, 1e-8)
+    }
+}
+
+impl<T: Element> Optimizer<T> for RmsProp<T> {
+    fn step(
+        &mut self,
+        slot: usize,
+        parameter: &mut Matrix<T>,
+        gradient: &Matrix<T>,
+        learning_rate: f32,
+    ) {
+        assert_same_shape(parameter, gradient);
+
+        let learning_rate = T::from_f32(learning_rate);
+        let (decay, epsilon) = (T::from_f32(self.decay), epsilon(self.epsilon));
+        let mean_square = state_buffer(&mut self.mean_square, slot, parameter);
+        for ((p, s), g) in parameter
+            .data
+            .iter_mut()
+            .zip(mean_square.data.iter_mut())
+            .zip(&gradient.data)
+        {
+            *s = decay * *s + (T::one() - decay) * *g * *g;
+            *p -= learning_rate * *g / (s.sqrt() + epsilon);
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, bound = "T: Element")]
+pub struct Adagrad<T = f32> {
+    pub epsilon: f32,
+    sum_square: Vec<Matrix<T>>,
+}
+
+impl<T: Element> Adagrad<T> {
+    pub fn new(epsilon: f32) -> Self {
+        Adagrad {
+            epsilon,
+            sum_square: Vec::new(),
+        }
+    }
+
+    pub fn cast<U: Element>(&self) -> Adagrad<U> {
+        Adagrad {
+            epsilon: self.epsilon,
+            sum_square: cast_state(&self.sum_square),
+        }
+    }
+}
+
+impl<T: Element> Default for Adagrad<T> {
+    fn default() -> Self {
+        Adagrad::new(1e-8)
+    }
+}
+
+impl<T: Element> Optimizer<T> for Adagrad<T> {
+    fn step(
+        &mut self,
+        slot: usize,
+        parameter: &mut Matrix<T>,
+        gradient: &Matrix<T>,
+        learning_rate: f32,
+    ) {
+        assert_same_shape(parameter, gradient);
+
+        let (learning_rate, epsilon) = (T::from_f32(learning_rate), epsilon(self.epsilon));
+        let sum_square = state_buffer(&mut self.sum_square, slot, parameter);
+        for ((p, s), g) in parameter
+            .data
+            .iter_mut()
+            .zip(sum_square.data.iter_mut())
+            .zip(&gradient.data)
+        {
+            *s += *g * *g;
+            *p -= learning_rate * *g / (s.sqrt() + epsilon);
+        }
+    }
+}
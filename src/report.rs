@@ -0,0 +1,226 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+// Classification metrics of a network over a labelled dataset, produced by `Network::evaluate`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EvaluationReport {
+    pub samples: usize,
+    // Average loss per sample
+    pub loss: f64,
+    pub accuracy: f64,
+    pub top_k: usize,
+    // Fraction of samples whose label is among the `top_k` largest outputs
+    pub top_k_accuracy: f64,
+    // `confusion_matrix[label][prediction]` counts the samples of each label predicted as each class
+    pub confusion_matrix: Vec<Vec<usize>>,
+    pub classes: Vec<ClassMetrics>,
+    // Unweighted mean over the classes
+    pub macro_average: Averages,
+    // Pooled over every sample, equal to the accuracy when each sample has one label
+    pub micro_average: Averages,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClassMetrics {
+    pub class: usize,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+    // Number of samples labelled as this class
+    pub support: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Averages {
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+impl EvaluationReport {
+    // Derives every metric from the confusion matrix, `top_k_hits` samples with their label in the
+    // top k outputs and the summed loss of every sample
+    pub fn from_counts(
+        confusion_matrix: Vec<Vec<usize>>,
+        top_k: usize,
+        top_k_hits: usize,
+        total_loss: f64,
+    ) -> Self {
+        let class_count = confusion_matrix.len();
+        let samples: usize = confusion_matrix.iter().flatten().sum();
+        let true_positives: Vec<usize> = (0..class_count).map(|c| confusion_matrix[c][c]).collect();
+        let correct: usize = true_positives.iter().sum();
+
+        let classes: Vec<ClassMetrics> = (0..class_count)
+            .map(|c| {
+                let support: usize = confusion_matrix[c].iter().sum();
+                let predicted: usize = confusion_matrix.iter().map(|row| row[c]).sum();
+                let precision = ratio(true_positives[c], predicted);
+                let recall = ratio(true_positives[c], support);
+
+                ClassMetrics {
+                    class: c,
+                    precision,
+                    recall,
+                    f1: f1(precision, recall),
+                    support,
+                }
+            })
+            .collect();
+
+        let mean = |metric: fn(&ClassMetrics) -> f64| {
+            classes.iter().map(metric).sum::<f64>() / class_count.max(1) as f64
+        };
+        let macro_average = Averages {
+            precision: mean(|class| class.precision),
+            recall: mean(|class| class.recall),
+            f1: mean(|class| class.f1),
+        };
+
+        // Every sample has one label and one prediction, so pooled false positives and false
+        // negatives are both the misclassified samples
+        let accuracy = ratio(correct, samples);
+        let micro_average = Averages {
+            precision: accuracy,
+            recall: accuracy,
+            f1: accuracy,
+        };
+
+        EvaluationReport {
+            samples,
+            loss: if samples == 0 {
+                0.0
+            } else {
+                total_loss / samples as f64
+            },
+            accuracy,
+            top_k,
+            top_k_accuracy: ratio(top_k_hits, samples),
+            confusion_matrix,
+            classes,
+            macro_average,
+            micro_average,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        // Only numbers and vectors of numbers, which always serialize
+        serde_json::to_string_pretty(self).expect("Failed to serialize evaluation report")
+    }
+
+    // Three sections separated by blank lines: one row per class followed by the macro and micro
+    // averages, the overall metrics, then the confusion matrix with a row per label
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("class,precision,recall,f1,support\n");
+
+        for class in &self.classes {
+            csv += &format!(
+                "{},{},{},{},{}\n",
+                class.class, class.precision, class.recall, class.f1, class.support
+            );
+        }
+        for (name, average) in [
+            ("macro", &self.macro_average),
+            ("micro", &self.micro_average),
+        ] {
+            csv += &format!(
+                "{name},{},{},{},{}\n",
+                average.precision, average.recall, average.f1, self.samples
+            );
+        }
+
+        csv += "\nmetric,value\n";
+        csv += &format!("samples,{}\n", self.samples);
+        csv += &format!("loss,{}\n", self.loss);
+        csv += &format!("accuracy,{}\n", self.accuracy);
+        csv += &format!("top_k,{}\n", self.top_k);
+        csv += &format!("top_k_accuracy,{}\n", self.top_k_accuracy);
+
+        csv += "\nlabel";
+        for c in 0..self.confusion_matrix.len() {
+            csv += &format!(",predicted_{c}");
+        }
+        for (label, row) in self.confusion_matrix.iter().enumerate() {
+            csv += &format!("\n{label}");
+            for count in row {
+                csv += &format!(",{count}");
+            }
+        }
+        csv.push('\n');
+
+        csv
+    }
+}
+
+impl fmt::Display for EvaluationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Samples:        {}", self.samples)?;
+        writeln!(f, "Average loss:   {:.4}", self.loss)?;
+        writeln!(f, "Accuracy:       {:.2}%", self.accuracy * 100.0)?;
+        writeln!(
+            f,
+            "Top-{} accuracy: {:.2}%",
+            self.top_k,
+            self.top_k_accuracy * 100.0
+        )?;
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<6} {:>9} {:>9} {:>9} {:>9}",
+            "Class", "Precision", "Recall", "F1", "Support"
+        )?;
+        for class in &self.classes {
+            writeln!(
+                f,
+                "{:<6} {:>9.4} {:>9.4} {:>9.4} {:>9}",
+                class.class, class.precision, class.recall, class.f1, class.support
+            )?;
+        }
+        for (name, average) in [
+            ("Macro", &self.macro_average),
+            ("Micro", &self.micro_average),
+        ] {
+            writeln!(
+                f,
+                "{name:<6} {:>9.4} {:>9.4} {:>9.4} {:>9}",
+                average.precision, average.recall, average.f1, self.samples
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Confusion matrix, rows are labels and columns predictions"
+        )?;
+        write!(f, "{:<6}", "")?;
+        for c in 0..self.confusion_matrix.len() {
+            write!(f, " {c:>6}")?;
+        }
+        for (label, row) in self.confusion_matrix.iter().enumerate() {
+            write!(f, "\n{label:<6}")?;
+            for count in row {
+                write!(f, " {count:>6}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+fn f1(precision: f64, recall: f64) -> f64 {
+    if precision + recall == 0.0 {
+        0.0
+    } else {
+        2.0 * precision * recall / (precision + recall)
+    }
+}
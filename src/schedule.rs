@@ -0,0 +1,188 @@
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+// How the learning rate changes over training, relative to the base learning rate of the network
+// unless stated otherwise. Epoch based schedules change the rate between epochs, step based ones
+// after every mini batch
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum LrSchedule {
+    #[default]
+    Constant,
+    // Multiplies the rate by `gamma` every `step_size` epochs
+    StepDecay {
+        step_size: u32,
+        gamma: f32,
+    },
+    // Multiplies the rate by `gamma` every epoch
+    Exponential {
+        gamma: f32,
+    },
+    // Anneals from the base rate to `min_lr` along a half cosine over `period` epochs, then
+    // restarts at the base rate with the period multiplied by `period_multiplier`
+    CosineAnnealing {
+        period: u32,
+        period_multiplier: u32,
+        min_lr: f32,
+    },
+    // Ramps the rate of `then` linearly up from zero over the first `steps` mini batches
+    Warmup {
+        steps: u64,
+        then: Box<LrSchedule>,
+    },
+    // Ignores the base rate, rising linearly from `max_lr / div_factor` to `max_lr` over the first
+    // `warmup_fraction` of all mini batches, then annealing along a half cosine to
+    // `max_lr / div_factor / final_div_factor` by the last one
+    OneCycle {
+        max_lr: f32,
+        warmup_fraction: f32,
+        div_factor: f32,
+        final_div_factor: f32,
+    },
+    // Multiplies the rate by `factor` once the validation loss has not improved by more than
+    // `min_delta` for `patience` epochs, never going below `min_lr`
+    ReduceOnPlateau {
+        factor: f32,
+        patience: u32,
+        min_delta: f32,
+        min_lr: f32,
+    },
+}
+
+// Tracks the position of training in a schedule, along with the state reduce on plateau needs
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Scheduler {
+    schedule: LrSchedule,
+    base_learning_rate: f32,
+    // Mini batches in the whole run, which one-cycle spreads its cycle over
+    total_steps: u64,
+    // Mini batches and epochs completed so far
+    steps: u64,
+    epochs: u32,
+    // Product of every reduction made by reduce on plateau
+    plateau_scale: f32,
+    best_loss: Option<f32>,
+    epochs_without_improvement: u32,
+}
+
+impl Scheduler {
+    pub fn new(schedule: LrSchedule, base_learning_rate: f32, total_steps: u64) -> Self {
+        Scheduler {
+            schedule,
+            base_learning_rate,
+            total_steps,
+            steps: 0,
+            epochs: 0,
+            plateau_scale: 1.0,
+            best_loss: None,
+            epochs_without_improvement: 0,
+        }
+    }
+
+    // Rate for the next mini batch
+    pub fn learning_rate(&self) -> f32 {
+        self.rate(&self.schedule)
+    }
+
+    // Call after every mini batch
+    pub fn step(&mut self) {
+        self.steps += 1;
+    }
+
+    // Call after every epoch with the loss reduce on plateau watches
+    pub fn end_epoch(&mut self, loss: f32) {
+        self.epochs += 1;
+
+        if let Some((factor, patience, min_delta)) = plateau(&self.schedule) {
+            if self.best_loss.is_none_or(|best| loss < best - min_delta) {
+                self.best_loss = Some(loss);
+                self.epochs_without_improvement = 0;
+            } else {
+                self.epochs_without_improvement += 1;
+                if self.epochs_without_improvement >= patience {
+                    self.plateau_scale *= factor;
+                    self.epochs_without_improvement = 0;
+                }
+            }
+        }
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn epochs(&self) -> u32 {
+        self.epochs
+    }
+
+    fn rate(&self, schedule: &LrSchedule) -> f32 {
+        let base = self.base_learning_rate;
+
+        match schedule {
+            LrSchedule::Constant => base,
+            LrSchedule::StepDecay { step_size, gamma } => {
+                base * gamma.powi((self.epochs / (*step_size).max(1)) as i32)
+            }
+            LrSchedule::Exponential { gamma } => base * gamma.powi(self.epochs as i32),
+            LrSchedule::CosineAnnealing {
+                period,
+                period_multiplier,
+                min_lr,
+            } => {
+                // Find the position within the current cycle
+                let mut position = self.epochs;
+                let mut period = (*period).max(1);
+                while position >= period {
+                    position -= period;
+                    period *= (*period_multiplier).max(1);
+                }
+
+                let progress = position as f32 / period as f32;
+                min_lr + (base - min_lr) * (1.0 + (PI * progress).cos()) / 2.0
+            }
+            LrSchedule::Warmup { steps, then } => {
+                let rate = self.rate(then);
+                if self.steps < *steps {
+                    rate * (self.steps + 1) as f32 / *steps as f32
+                } else {
+                    rate
+                }
+            }
+            LrSchedule::OneCycle {
+                max_lr,
+                warmup_fraction,
+                div_factor,
+                final_div_factor,
+            } => {
+                let initial = max_lr / div_factor;
+                let last = initial / final_div_factor;
+                let warmup_steps = (self.total_steps as f32 * warmup_fraction).round() as u64;
+
+                if self.steps < warmup_steps {
+                    initial + (max_lr - initial) * self.steps as f32 / warmup_steps as f32
+                } else {
+                    // Reaches the final rate on the last mini batch
+                    let annealing_steps = self.total_steps.saturating_sub(warmup_steps + 1).max(1);
+                    let progress =
+                        ((self.steps - warmup_steps) as f32 / annealing_steps as f32).min(1.0);
+                    last + (max_lr - last) * (1.0 + (PI * progress).cos()) / 2.0
+                }
+            }
+            LrSchedule::ReduceOnPlateau { min_lr, .. } => (base * self.plateau_scale).max(*min_lr),
+        }
+    }
+}
+
+// Factor, patience and min delta of a reduce on plateau schedule, including one after a warmup
+fn plateau(schedule: &LrSchedule) -> Option<(f32, u32, f32)> {
+    match schedule {
+        LrSchedule::ReduceOnPlateau {
+            factor,
+            patience,
+            min_delta,
+            ..
+        } => Some((*factor, *patience, *min_delta)),
+        LrSchedule::Warmup { then, .. } => plateau(then),
+        _ => None,
+    }
+}
@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    element::Element,
+    error::{Error, Result},
+    matrix::Matrix,
+    schedule::LrSchedule,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrainingConfig {
+    pub epochs: u16,
+    pub mini_batch_size: usize,
+    // Skip the final mini batch of an epoch when it is smaller than `mini_batch_size`
+    pub drop_last: bool,
+    // Seed for shuffling the training data, defaults to the seed of the network
+    pub seed: Option<u64>,
+    // How the accuracy reported for each epoch is measured
+    pub evaluation: Evaluation,
+    // Fraction of the training samples held out to validate on after every epoch, never trained on
+    pub validation_split: f32,
+    // Stops once the validation metric stops improving, needs a validation split
+    pub early_stopping: Option<EarlyStopping>,
+    // Reduce on plateau watches the validation loss, or the training loss without a validation split
+    pub lr_schedule: LrSchedule,
+    pub checkpoint: Option<Checkpointing>,
+    // Checkpoint to carry on training from, its network replaces the one being trained
+    pub resume_from: Option<String>,
+}
+
+impl Default for TrainingConfig {
+    fn default() -> Self {
+        TrainingConfig {
+            epochs: 30,
+            mini_batch_size: 10,
+            drop_last: false,
+            seed: None,
+            evaluation: Evaluation::default(),
+            validation_split: 0.0,
+            early_stopping: None,
+            lr_schedule: LrSchedule::default(),
+            checkpoint: None,
+            resume_from: None,
+        }
+    }
+}
+
+impl TrainingConfig {
+    // Catches settings that cannot train on any data
+    pub fn validate(&self) -> Result<()> {
+        if self.mini_batch_size == 0 {
+            return Err(Error::InvalidConfig(
+                "Mini batch size must be positive".to_string(),
+            ));
+        }
+        if !(0.0..1.0).contains(&self.validation_split) {
+            return Err(Error::InvalidConfig(format!(
+                "Validation split must be in [0, 1), found {}",
+                self.validation_split
+            )));
+        }
+        if self.early_stopping.is_some() && self.validation_split == 0.0 {
+            return Err(Error::InvalidConfig(
+                "Early stopping needs a validation split".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+// Overwrites the checkpoint at `path` every interval, with the network, optimizer, shuffling and
+// schedule state needed to resume
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpointing {
+    pub path: String,
+    pub every: CheckpointInterval,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CheckpointInterval {
+    Epochs(u16),
+    // Mini batches, which can fall in the middle of an epoch
+    Steps(u64),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EarlyStopping {
+    pub monitor: Monitor,
+    // Epochs without improvement to wait before stopping
+    pub patience: u16,
+    // Smallest change of the monitored metric that counts as an improvement
+    pub min_delta: f32,
+    // Restore the weights and biases of the best epoch once training ends
+    pub restore_best_weights: bool,
+}
+
+impl Default for EarlyStopping {
+    fn default() -> Self {
+        EarlyStopping {
+            monitor: Monitor::ValidationLoss,
+            patience: 5,
+            min_delta: 0.0,
+            restore_best_weights: true,
+        }
+    }
+}
+
+impl EarlyStopping {
+    // Whether `value` beats `best` by more than `min_delta`
+    pub fn improves(&self, value: f32, best: f32) -> bool {
+        match self.monitor {
+            Monitor::ValidationLoss => value < best - self.min_delta,
+            Monitor::ValidationAccuracy => value > best + self.min_delta,
+        }
+    }
+}
+
+// Validation metric early stopping watches, loss is minimized and accuracy maximized
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Monitor {
+    ValidationLoss,
+    ValidationAccuracy,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EpochStats {
+    pub epoch: u16,
+    pub loss: f32,
+    // Fraction of training samples the network got right while training this epoch
+    pub accuracy: f32,
+    // Loss and accuracy on the held out validation samples after this epoch, when there are any
+    pub validation_loss: Option<f32>,
+    pub validation_accuracy: Option<f32>,
+    // Learning rate of the last mini batch of this epoch
+    pub learning_rate: f32,
+    pub duration: Duration,
+    // Time spent training since training started, counting the time before a resume
+    #[serde(default)]
+    pub elapsed: Duration,
+}
+
+impl EpochStats {
+    pub fn monitored(&self, monitor: Monitor) -> Option<f32> {
+        match monitor {
+            Monitor::ValidationLoss => self.validation_loss,
+            Monitor::ValidationAccuracy => self.validation_accuracy,
+        }
+    }
+}
+
+// Decides whether an output of the network counts as correct
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Evaluation {
+    // The largest output has to be at the index of the largest expected output, e.g. one-hot labels.
+    // A single output is a binary class instead, the output and its label are split at 0.5
+    #[default]
+    Classification,
+    // Every output has to be within `tolerance` of its expected value
+    Regression {
+        tolerance: f32,
+    },
+}
+
+// Class of column `j`, the index of its largest value or, for a single row, whether it is at least 0.5
+pub(crate) fn class_of<T: Element>(matrix: &Matrix<T>, j: usize) -> usize {
+    if matrix.rows == 1 {
+        (matrix.data[j] >= T::from_f32(0.5)) as usize
+    } else {
+        matrix.argmax_col(j)
+    }
+}
+
+impl Evaluation {
+    // Number of columns of `outputs` that count as correct against the matching column of `expected`
+    pub fn passes<T: Element>(&self, outputs: &Matrix<T>, expected: &Matrix<T>) -> usize {
+        assert!(
+            outputs.rows == expected.rows && outputs.cols == expected.cols,
+            "Attempt to evaluate outputs of size {}x{} against expected outputs of size {}x{}",
+            outputs.rows,
+            outputs.cols,
+            expected.rows,
+            expected.cols
+        );
+
+        match self {
+            Evaluation::Classification => (0..outputs.cols)
+                .filter(|&j| class_of(outputs, j) == class_of(expected, j))
+                .count(),
+            Evaluation::Regression { tolerance } => {
+                let tolerance = T::from_f32(*tolerance);
+                (0..outputs.cols)
+                    .filter(|&j| {
+                        (0..outputs.rows).all(|i| {
+                            let index = i * outputs.cols + j;
+                            (outputs.data[index] - expected.data[index]).abs() <= tolerance
+                        })
+                    })
+                    .count()
+            }
+        }
+    }
+}
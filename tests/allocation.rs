@@ -0,0 +1,90 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use neural_network_ml::{
+    ActivationFunction, Network,
+    callback::{BatchStats, Callback, Control},
+    training::TrainingConfig,
+};
+
+// Counts every allocation made by the test binary
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        // SAFETY: forwards the caller's layout unchanged
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: `ptr` was allocated by `System` with this layout
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Allocations made between consecutive mini batches
+#[derive(Default)]
+struct BatchAllocations {
+    last: Option<usize>,
+    counts: Vec<usize>,
+}
+
+impl Callback for BatchAllocations {
+    fn on_batch_end(&mut self, _network: &Network, _batch: &BatchStats) -> Control {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        if let Some(last) = self.last {
+            self.counts.push(allocations - last);
+        }
+        self.last = Some(allocations);
+
+        Control::Continue
+    }
+}
+
+#[test]
+fn training_steps_reuse_their_buffers() {
+    // Large enough for the blocked matrix multiplication, which packs its operands
+    let mut network: Network = Network::builder(200)
+        .layer(64, ActivationFunction::Sigmoid)
+        .layer(10, ActivationFunction::Softmax)
+        .seed(1)
+        .build();
+    let inputs: Vec<Vec<f32>> = (0..400)
+        .map(|i| (0..200).map(|j| ((i * j) % 7) as f32 / 7.0).collect())
+        .collect();
+    let targets: Vec<Vec<f32>> = (0..400)
+        .map(|i| {
+            (0..10)
+                .map(|j| if i % 10 == j { 1.0 } else { 0.0 })
+                .collect()
+        })
+        .collect();
+    let config = TrainingConfig {
+        epochs: 1,
+        mini_batch_size: 10,
+        ..TrainingConfig::default()
+    };
+
+    let mut allocations = BatchAllocations::default();
+    network.stochastic_train_with(&inputs, &targets, &config, &mut [&mut allocations]);
+
+    // Rayon can allocate now and then when queueing work, but not on every step
+    let steps_without = allocations
+        .counts
+        .iter()
+        .filter(|count| **count == 0)
+        .count();
+    assert!(
+        steps_without * 2 > allocations.counts.len(),
+        "Allocations between mini batches: {:?}",
+        allocations.counts
+    );
+}
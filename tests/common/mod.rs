@@ -0,0 +1,62 @@
+// Fixtures shared by the integration tests, each test crate only uses some of them
+#![allow(dead_code)]
+
+use std::{env, fs};
+
+use neural_network_ml::{ActivationFunction, Network};
+
+pub fn network(seed: u64) -> Network<f64> {
+    Network::builder(2)
+        .layer(4, ActivationFunction::Tanh)
+        .layer(2, ActivationFunction::Sigmoid)
+        .learning_rate(0.5)
+        .seed(seed)
+        .build()
+}
+
+// XOR with one-hot targets, repeated up to count samples
+pub fn samples(count: usize) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let inputs = [[0.0, 0.0], [0.0, 1.0], [1.0, 0.0], [1.0, 1.0]];
+    let targets = [[1.0, 0.0], [0.0, 1.0], [0.0, 1.0], [1.0, 0.0]];
+
+    (
+        inputs
+            .iter()
+            .cycle()
+            .take(count)
+            .map(|x| x.to_vec())
+            .collect(),
+        targets
+            .iter()
+            .cycle()
+            .take(count)
+            .map(|y| y.to_vec())
+            .collect(),
+    )
+}
+
+// A path in the temporary directory, removed on drop so a failing test doesn't leave it behind
+pub struct TemporaryFile {
+    path: String,
+}
+
+impl TemporaryFile {
+    pub fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("{name}-{}", std::process::id()));
+
+        TemporaryFile {
+            path: path.to_str().unwrap().to_string(),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Drop for TemporaryFile {
+    fn drop(&mut self) {
+        // The test may have failed before creating it
+        let _ = fs::remove_file(&self.path);
+    }
+}
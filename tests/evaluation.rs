@@ -0,0 +1,89 @@
+use neural_network_ml::{
+    ActivationFunction, Error, Loss, Matrix, Network, initializer::Initializer,
+    training::Evaluation,
+};
+
+// Three samples as columns, the first correct with its largest output under 0.5
+fn outputs() -> Matrix {
+    Matrix::from(vec![
+        vec![0.49, 0.6, 0.1],
+        vec![0.3, 0.7, 0.2],
+        vec![0.21, 0.1, 0.7],
+    ])
+}
+
+fn labels() -> Matrix {
+    Matrix::from(vec![
+        vec![1.0, 1.0, 0.0],
+        vec![0.0, 0.0, 0.0],
+        vec![0.0, 0.0, 1.0],
+    ])
+}
+
+#[test]
+fn classification_compares_argmax() {
+    assert_eq!(Evaluation::Classification.passes(&outputs(), &labels()), 2);
+}
+
+#[test]
+fn regression_needs_every_output_within_tolerance() {
+    assert_eq!(
+        Evaluation::Regression { tolerance: 0.45 }.passes(&outputs(), &labels()),
+        1
+    );
+    assert_eq!(
+        Evaluation::Regression { tolerance: 0.8 }.passes(&outputs(), &labels()),
+        3
+    );
+}
+
+#[test]
+fn single_output_classification_splits_at_half() {
+    let outputs = Matrix::from(vec![vec![0.2, 0.7, 0.4, 0.9]]);
+    let labels = Matrix::from(vec![vec![0.0, 1.0, 1.0, 0.0]]);
+
+    assert_eq!(Evaluation::Classification.passes(&outputs, &labels), 2);
+}
+
+// A sigmoid output scored as a binary class, an argmax over one row would pass every sample
+#[test]
+fn binary_network_accuracy() {
+    let network: Network<f64> = Network::builder(1)
+        .layer_with_initializer(1, ActivationFunction::Sigmoid, Initializer::Constant(1.0))
+        .loss(Loss::BinaryCrossEntropy)
+        .build();
+    // Outputs are sigmoid(x), above 0.5 exactly for positive inputs
+    let inputs = vec![vec![-2.0], vec![-1.0], vec![1.0], vec![2.0]];
+    let labels = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+
+    let accuracy = network.accuracy(&inputs, &labels, Evaluation::Classification);
+    let report = network.evaluate(&inputs, &labels, 1).unwrap();
+
+    assert_eq!(accuracy.unwrap(), 0.5);
+    assert_eq!(report.confusion_matrix, vec![vec![1, 1], vec![1, 1]]);
+    assert_eq!(report.accuracy, 0.5);
+    assert_eq!(report.top_k_accuracy, 0.5);
+}
+
+#[test]
+fn scoring_no_samples_is_an_error() {
+    let network: Network<f64> = Network::builder(2)
+        .layer(2, ActivationFunction::Sigmoid)
+        .seed(0)
+        .build();
+
+    assert!(matches!(
+        network.accuracy(&[], &[], Evaluation::Classification),
+        Err(Error::LengthMismatch {
+            expected: 1,
+            found: 0
+        })
+    ));
+    assert!(matches!(
+        network.test(&[], &[], Evaluation::Classification, &mut Vec::new()),
+        Err(Error::LengthMismatch {
+            expected: 1,
+            found: 0
+        })
+    ));
+}
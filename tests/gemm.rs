@@ -0,0 +1,104 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use neural_network_ml::gemm::{Operand, gemm, gemm_naive};
+
+// (m, n, k) shapes past the small loop, with edges that leave partial micro tiles and a depth
+// spanning more than one packed block
+const SHAPES: [(usize, usize, usize); 5] = [
+    (33, 33, 33),
+    (64, 16, 256),
+    (65, 17, 257),
+    (5, 130, 600),
+    (130, 7, 90),
+];
+
+fn random_data(len: usize, rng: &mut ChaCha8Rng) -> Vec<f32> {
+    (0..len).map(|_| rng.random_range(-1.0..1.0)).collect()
+}
+
+fn assert_close(product: &[f32], expected: &[f32], k: usize) {
+    for (x, y) in product.iter().zip(expected) {
+        assert!(
+            (x - y).abs() <= 1e-5 * k as f32,
+            "{x} and {y} differ by more than the rounding error"
+        );
+    }
+}
+
+#[test]
+fn blocked_multiply_matches_reference() {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+    for (m, n, k) in SHAPES {
+        let a = random_data(m * k, &mut rng);
+        let b = random_data(k * n, &mut rng);
+        // Starts from non zero values as the product is added to the result
+        let initial = random_data(m * n, &mut rng);
+
+        let mut expected = initial.clone();
+        gemm_naive(
+            m,
+            n,
+            k,
+            Operand::row_major(&a, k),
+            Operand::row_major(&b, n),
+            &mut expected,
+        );
+
+        let mut product = initial;
+        gemm(
+            m,
+            n,
+            k,
+            Operand::row_major(&a, k),
+            Operand::row_major(&b, n),
+            &mut product,
+        );
+
+        assert_close(&product, &expected, k);
+    }
+}
+
+#[test]
+fn blocked_multiply_reads_strided_operands() {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+
+    for (m, n, k) in SHAPES {
+        // Column-major copies, read through swapped strides
+        let a = random_data(m * k, &mut rng);
+        let b = random_data(k * n, &mut rng);
+        let a_t: Vec<f32> = (0..m * k).map(|i| a[(i % m) * k + i / m]).collect();
+        let b_t: Vec<f32> = (0..k * n).map(|i| b[(i % k) * n + i / k]).collect();
+
+        let mut expected = vec![0.0; m * n];
+        gemm_naive(
+            m,
+            n,
+            k,
+            Operand::row_major(&a, k),
+            Operand::row_major(&b, n),
+            &mut expected,
+        );
+
+        let mut product = vec![0.0; m * n];
+        gemm(
+            m,
+            n,
+            k,
+            Operand {
+                data: &a_t,
+                row_stride: 1,
+                col_stride: m,
+            },
+            Operand {
+                data: &b_t,
+                row_stride: 1,
+                col_stride: k,
+            },
+            &mut product,
+        );
+
+        assert_close(&product, &expected, k);
+    }
+}
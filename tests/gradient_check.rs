@@ -0,0 +1,129 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use neural_network_ml::{ActivationFunction, Error, Loss, Network, initializer::Initializer};
+
+const EPSILON: f64 = 1e-6;
+const TOLERANCE: f64 = 1e-6;
+const INPUT_SIZE: usize = 4;
+const OUTPUT_SIZE: usize = 3;
+const BATCH_SIZE: usize = 5;
+
+const HIDDEN_ACTIVATIONS: [ActivationFunction; 11] = [
+    ActivationFunction::Identity,
+    ActivationFunction::Sigmoid,
+    ActivationFunction::Tanh,
+    ActivationFunction::Relu,
+    ActivationFunction::LeakyRelu { slope: 0.1 },
+    ActivationFunction::Elu { alpha: 0.5 },
+    ActivationFunction::Selu,
+    ActivationFunction::Gelu,
+    ActivationFunction::Swish,
+    ActivationFunction::Softplus,
+    ActivationFunction::Softmax,
+];
+
+// Cross-entropy losses need outputs in (0, 1), or at least positive, to be differentiable
+const PROBABILITY_OUTPUTS: [ActivationFunction; 2] =
+    [ActivationFunction::Sigmoid, ActivationFunction::Softmax];
+const POSITIVE_OUTPUTS: [ActivationFunction; 3] = [
+    ActivationFunction::Sigmoid,
+    ActivationFunction::Softmax,
+    ActivationFunction::Softplus,
+];
+
+fn check(hidden: ActivationFunction, output: ActivationFunction, loss: Loss) {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let mut network: Network<f64> = Network::builder(INPUT_SIZE)
+        .layer(6, hidden)
+        .layer(5, hidden)
+        .layer(OUTPUT_SIZE, output)
+        .bias_initializer(Initializer::Normal {
+            mean: 0.0,
+            std_dev: 0.1,
+        })
+        .loss(loss)
+        .seed(1)
+        .build();
+
+    let inputs: Vec<Vec<f64>> = (0..BATCH_SIZE)
+        .map(|_| {
+            (0..INPUT_SIZE)
+                .map(|_| rng.random_range(-1.0..1.0))
+                .collect()
+        })
+        .collect();
+    let expected_outputs: Vec<Vec<f64>> = (0..BATCH_SIZE)
+        .map(|i| match loss {
+            Loss::CrossEntropy => (0..OUTPUT_SIZE)
+                .map(|j| if j == i % OUTPUT_SIZE { 1.0 } else { 0.0 })
+                .collect(),
+            Loss::BinaryCrossEntropy => (0..OUTPUT_SIZE)
+                .map(|_| rng.random_range(0.0..1.0))
+                .collect(),
+            _ => (0..OUTPUT_SIZE)
+                .map(|_| rng.random_range(-1.0..1.0))
+                .collect(),
+        })
+        .collect();
+
+    let report = network
+        .gradient_check(&inputs, &expected_outputs, EPSILON)
+        .unwrap();
+
+    for layer in report {
+        assert!(
+            layer.max_error() < TOLERANCE,
+            "{hidden:?} hidden layers with {output:?} output and {loss:?} loss: {layer:?}"
+        );
+    }
+}
+
+fn check_all(outputs: &[ActivationFunction], loss: Loss) {
+    for &hidden in &HIDDEN_ACTIVATIONS {
+        for &output in outputs {
+            check(hidden, output, loss);
+        }
+    }
+}
+
+#[test]
+fn mean_squared_error() {
+    check_all(&HIDDEN_ACTIVATIONS, Loss::MeanSquaredError);
+}
+
+#[test]
+fn mean_absolute_error() {
+    check_all(&HIDDEN_ACTIVATIONS, Loss::MeanAbsoluteError);
+}
+
+#[test]
+fn huber() {
+    check_all(&HIDDEN_ACTIVATIONS, Loss::Huber { delta: 0.5 });
+}
+
+#[test]
+fn binary_cross_entropy() {
+    check_all(&PROBABILITY_OUTPUTS, Loss::BinaryCrossEntropy);
+}
+
+#[test]
+fn cross_entropy() {
+    check_all(&POSITIVE_OUTPUTS, Loss::CrossEntropy);
+}
+
+#[test]
+fn empty_batch_is_an_error() {
+    let mut network: Network<f64> = Network::builder(INPUT_SIZE)
+        .layer(OUTPUT_SIZE, ActivationFunction::Sigmoid)
+        .seed(0)
+        .build();
+
+    assert!(matches!(
+        network.gradient_check(&[], &[], EPSILON),
+        Err(Error::LengthMismatch {
+            expected: 1,
+            found: 0
+        })
+    ));
+}
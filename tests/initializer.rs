@@ -0,0 +1,117 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use neural_network_ml::{
+    ActivationFunction, Loss, Matrix, Network, initializer::Initializer, optimizer::OptimizerKind,
+};
+
+fn initialize(initializer: Initializer, rows: usize, cols: usize) -> Matrix<f64> {
+    initializer.initialize(rows, cols, &mut ChaCha8Rng::seed_from_u64(0))
+}
+
+fn mean_and_variance(data: &[f64]) -> (f64, f64) {
+    let mean = data.iter().sum::<f64>() / data.len() as f64;
+    let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / data.len() as f64;
+
+    (mean, variance)
+}
+
+// Products of every pair of rows, or of columns when those are the shorter dimension
+fn gram(matrix: &Matrix<f64>) -> Vec<Vec<f64>> {
+    let (count, length) = (matrix.rows.min(matrix.cols), matrix.rows.max(matrix.cols));
+    let at = |k: usize, l: usize| {
+        if matrix.rows <= matrix.cols {
+            matrix.data[k * matrix.cols + l]
+        } else {
+            matrix.data[l * matrix.cols + k]
+        }
+    };
+
+    (0..count)
+        .map(|a| {
+            (0..count)
+                .map(|b| (0..length).map(|l| at(a, l) * at(b, l)).sum())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn orthogonal_vectors_are_orthonormal_up_to_the_gain() {
+    let gain: f64 = 1.5;
+
+    for (rows, cols) in [(3, 7), (7, 3), (5, 5)] {
+        let matrix = initialize(Initializer::Orthogonal { gain: gain as f32 }, rows, cols);
+
+        for (a, row) in gram(&matrix).iter().enumerate() {
+            for (b, product) in row.iter().enumerate() {
+                let expected = if a == b { gain * gain } else { 0.0 };
+                assert!(
+                    (product - expected).abs() < 1e-9,
+                    "{rows}x{cols} gives {product} at ({a}, {b}) instead of {expected}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn scaled_initializers_match_their_variance() {
+    // 200 outputs by 100 inputs
+    let (rows, cols) = (200, 100);
+    let (fan_in, fan_out) = (cols as f64, rows as f64);
+    let cases = [
+        (Initializer::XavierUniform, 2.0 / (fan_in + fan_out)),
+        (Initializer::XavierNormal, 2.0 / (fan_in + fan_out)),
+        (Initializer::HeUniform, 2.0 / fan_in),
+        (Initializer::HeNormal, 2.0 / fan_in),
+        (Initializer::LecunUniform, 1.0 / fan_in),
+        (Initializer::LecunNormal, 1.0 / fan_in),
+    ];
+
+    for (initializer, expected) in cases {
+        let (mean, variance) = mean_and_variance(&initialize(initializer, rows, cols).data);
+
+        // 20000 draws keep the sample variance within a few percent
+        assert!(
+            mean.abs() < 0.05 * expected.sqrt(),
+            "{initializer:?} has mean {mean}"
+        );
+        assert!(
+            (variance / expected - 1.0).abs() < 0.05,
+            "{initializer:?} has variance {variance} instead of {expected}"
+        );
+    }
+}
+
+#[test]
+fn biases_default_to_zero() {
+    let layers = vec![
+        (4, ActivationFunction::Relu),
+        (3, ActivationFunction::Sigmoid),
+    ];
+    let constructed: Network<f64> = Network::new(
+        2,
+        layers.clone(),
+        Loss::MeanSquaredError,
+        0.1,
+        OptimizerKind::default(),
+        Some(1),
+    );
+    let built: Network<f64> = Network::builder(2).layers(layers).seed(1).build();
+
+    for network in [&constructed, &built] {
+        assert!(
+            network
+                .weights()
+                .iter()
+                .any(|w| w.data.iter().any(|&x| x != 0.0))
+        );
+        assert!(
+            network
+                .biases()
+                .iter()
+                .all(|b| b.data.iter().all(|&x| x == 0.0))
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use proptest::prelude::*;
+
+use neural_network_ml::{
+    Error, Matrix,
+    gemm::{Operand, gemm_naive},
+};
+
+fn matrix<T>(rows: usize, cols: usize, data: Vec<T>) -> Matrix<T> {
+    Matrix { rows, cols, data }
+}
+
+// Equal shapes and every element within `tolerance` scaled by the larger magnitude
+fn assert_close(lhs: &Matrix<f64>, rhs: &Matrix<f64>, tolerance: f64) {
+    assert_eq!((lhs.rows, lhs.cols), (rhs.rows, rhs.cols));
+
+    for (a, b) in lhs.data.iter().zip(&rhs.data) {
+        let scale = 1.0f64.max(a.abs()).max(b.abs());
+        assert!(
+            (a - b).abs() <= tolerance * scale,
+            "{a} and {b} differ by more than {tolerance}"
+        );
+    }
+}
+
+fn naive_product(lhs: &Matrix, rhs: &Matrix) -> Matrix {
+    let mut product = Matrix::zeros(lhs.rows, rhs.cols);
+    gemm_naive(
+        lhs.rows,
+        rhs.cols,
+        lhs.cols,
+        Operand::row_major(&lhs.data, lhs.cols),
+        Operand::row_major(&rhs.data, rhs.cols),
+        &mut product.data,
+    );
+    product
+}
+
+fn assert_shape_mismatch<T>(result: Result<T, Error>, operation: &str) {
+    match result {
+        Err(Error::ShapeMismatch {
+            operation: found, ..
+        }) => assert_eq!(found, operation),
+        Err(error) => panic!("Expected a shape mismatch, found {error}"),
+        Ok(_) => panic!("Expected a shape mismatch"),
+    }
+}
+
+#[test]
+fn from_nested_vec_is_row_major() {
+    let m = Matrix::from(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+
+    assert_eq!((m.rows, m.cols), (2, 3));
+    assert_eq!(m.data, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    assert_eq!(
+        Matrix::from(&vec![vec![1.0], vec![2.0]]).data,
+        vec![1.0, 2.0]
+    );
+}
+
+#[test]
+fn from_vec_is_row_vector() {
+    let m = Matrix::from(vec![1.0, 2.0, 3.0]);
+
+    assert_eq!((m.rows, m.cols), (1, 3));
+    assert_eq!(Matrix::from(&vec![1.0, 2.0]).data, vec![1.0, 2.0]);
+}
+
+#[test]
+fn index_returns_rows() {
+    let m = Matrix::from(vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]);
+
+    assert_eq!(&m[0], &[1.0, 2.0]);
+    assert_eq!(&m[2], &[5.0, 6.0]);
+}
+
+#[test]
+fn transpose_swaps_rows_and_columns() {
+    let m = Matrix::from(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+    let t = m.transpose();
+
+    assert_eq!((t.rows, t.cols), (3, 2));
+    assert_eq!(t.data, vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+}
+
+#[test]
+fn multiply_known_product() {
+    let a = Matrix::from(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+    let b = Matrix::from(vec![vec![7.0, 8.0], vec![9.0, 10.0], vec![11.0, 12.0]]);
+    let product = &a * &b;
+
+    assert_eq!((product.rows, product.cols), (2, 2));
+    assert_eq!(product.data, vec![58.0, 64.0, 139.0, 154.0]);
+    assert_eq!(
+        a.matmul_tn(&a.transpose().transpose()).data,
+        (a.transpose() * &a).data
+    );
+    assert_eq!(a.matmul_nt(&a).data, (&a * &a.transpose()).data);
+}
+
+#[test]
+fn elementwise_operations() {
+    let a = Matrix::from(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+    let b = Matrix::from(vec![vec![5.0, 6.0], vec![7.0, 8.0]]);
+
+    assert_eq!(a.dot(&b).data, vec![5.0, 12.0, 21.0, 32.0]);
+    assert_eq!((&a + &b).data, vec![6.0, 8.0, 10.0, 12.0]);
+    assert_eq!((&b - &a).data, vec![4.0, 4.0, 4.0, 4.0]);
+    assert_eq!((&a * 2.0).data, vec![2.0, 4.0, 6.0, 8.0]);
+    assert_eq!(a.map(|x| x * x).data, vec![1.0, 4.0, 9.0, 16.0]);
+}
+
+#[test]
+fn in_place_operations_match_allocating_ones() {
+    let a = Matrix::from(vec![vec![1.0, -2.0], vec![3.0, 0.5]]);
+    let b = Matrix::from(vec![vec![0.5, 6.0], vec![-7.0, 8.0]]);
+
+    let mut m = a.clone();
+    m += &b;
+    assert_eq!(m.data, (&a + &b).data);
+
+    let mut m = a.clone();
+    m -= &b;
+    assert_eq!(m.data, (&a - &b).data);
+
+    let mut m = a.clone();
+    m *= 3.0;
+    assert_eq!(m.data, (&a * 3.0).data);
+
+    let mut m = a.clone();
+    m.hadamard_inplace(&b);
+    assert_eq!(m.data, a.dot(&b).data);
+
+    let mut m = a.clone();
+    m.scaled_add(-0.5, &b);
+    assert_eq!(m.data, (&a - &(&b * 0.5)).data);
+
+    let mut m = a.clone();
+    m.map_inplace(f32::abs);
+    assert_eq!(m.data, a.map(f32::abs).data);
+}
+
+#[test]
+fn matmul_into_reshapes_the_output() {
+    let a = Matrix::from(vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]);
+    let b = Matrix::from(vec![vec![1.0, 0.0, 2.0], vec![0.0, 1.0, 3.0]]);
+    let mut out = Matrix::from(vec![9.0; 20]);
+
+    a.matmul_into(&b, &mut out);
+
+    assert_eq!((out.rows, out.cols), (3, 3));
+    assert_eq!(out.data, (&a * &b).data);
+}
+
+#[test]
+fn reductions() {
+    let m = Matrix::from(vec![vec![1.0, -2.0, 3.0], vec![4.0, 5.0, -6.0]]);
+
+    let rows = m.sum_rows();
+    assert_eq!((rows.rows, rows.cols), (1, 3));
+    assert_eq!(rows.data, vec![5.0, 3.0, -3.0]);
+
+    let cols = m.sum_cols();
+    assert_eq!((cols.rows, cols.cols), (2, 1));
+    assert_eq!(cols.data, vec![2.0, 3.0]);
+
+    assert_eq!(m.mean(), 5.0 / 6.0);
+    assert_eq!(m.max(), 5.0);
+    assert_eq!(m.argmax_per_col(), vec![1, 1, 0]);
+    assert_eq!(Matrix::from(vec![vec![3.0, 4.0]]).norm(), 5.0);
+}
+
+#[test]
+fn argmax_takes_the_first_of_equal_maxima() {
+    let m = Matrix::from(vec![vec![1.0, 0.0], vec![1.0, 0.0]]);
+
+    assert_eq!(m.argmax_per_col(), vec![0, 0]);
+}
+
+#[test]
+fn broadcast_and_clip() {
+    let mut m = Matrix::from(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+
+    m.broadcast_add_col(&Matrix::from(vec![vec![10.0], vec![20.0]]));
+    assert_eq!(m.data, vec![11.0, 12.0, 23.0, 24.0]);
+
+    m.broadcast_add_row(&Matrix::from(vec![-1.0, 1.0]));
+    assert_eq!(m.data, vec![10.0, 13.0, 22.0, 25.0]);
+
+    assert_eq!(m.clip(12.0, 24.0).data, vec![12.0, 13.0, 22.0, 24.0]);
+    m.clip_inplace(0.0, 20.0);
+    assert_eq!(m.data, vec![10.0, 13.0, 20.0, 20.0]);
+}
+
+#[test]
+fn shape_mismatches_are_errors() {
+    let a: Matrix = Matrix::zeros(2, 3);
+    let b: Matrix = Matrix::zeros(3, 2);
+    let mut c: Matrix = Matrix::zeros(2, 3);
+
+    assert_shape_mismatch(a.try_add(&b), "add");
+    assert_shape_mismatch(a.try_sub(&b), "subtract");
+    assert_shape_mismatch(a.try_dot(&b), "dot multiply");
+    assert_shape_mismatch(a.try_mul(&a), "multiply");
+    assert_shape_mismatch(a.try_matmul_tn(&b), "multiply");
+    assert_shape_mismatch(a.try_matmul_nt(&b), "multiply");
+    assert_shape_mismatch(a.try_matmul_into(&a, &mut c), "multiply");
+    assert_shape_mismatch(c.try_hadamard_inplace(&b), "dot multiply");
+    assert_shape_mismatch(c.try_scaled_add(1.0, &b), "add");
+    assert_shape_mismatch(c.try_broadcast_add_col(&b), "broadcast add");
+    assert_shape_mismatch(c.try_broadcast_add_row(&a), "broadcast add");
+}
+
+#[test]
+#[should_panic(expected = "Attempt to add matrix of size 2x3 with matrix of size 3x2")]
+fn add_operator_panics_on_mismatch() {
+    let _ = &Matrix::<f32>::zeros(2, 3) + &Matrix::zeros(3, 2);
+}
+
+#[test]
+#[should_panic(expected = "Attempt to multiply matrix of size 2x3 with matrix of size 2x3")]
+fn multiply_operator_panics_on_mismatch() {
+    let _ = &Matrix::<f32>::zeros(2, 3) * &Matrix::zeros(2, 3);
+}
+
+#[test]
+#[should_panic(expected = "Attempt to subtract")]
+fn sub_assign_panics_on_mismatch() {
+    let mut m: Matrix = Matrix::zeros(2, 2);
+    m -= &Matrix::zeros(1, 2);
+}
+
+fn any_matrix(rows: usize, cols: usize) -> impl Strategy<Value = Matrix<f64>> {
+    prop::collection::vec(-10.0..10.0f64, rows * cols)
+        .prop_map(move |data| matrix(rows, cols, data))
+}
+
+fn shape() -> impl Strategy<Value = (usize, usize)> {
+    (1..8usize, 1..8usize)
+}
+
+proptest! {
+    #[test]
+    fn transpose_is_an_involution(m in shape().prop_flat_map(|(r, c)| any_matrix(r, c))) {
+        let twice = m.transpose().transpose();
+        prop_assert_eq!((twice.rows, twice.cols), (m.rows, m.cols));
+        prop_assert_eq!(twice.data, m.data);
+    }
+
+    #[test]
+    fn add_and_sub_are_inverses(
+        (a, b) in shape().prop_flat_map(|(r, c)| (any_matrix(r, c), any_matrix(r, c)))
+    ) {
+        assert_close(&(&(&a + &b) - &b), &a, 1e-12);
+        prop_assert!((&a - &a).data.iter().all(|x| *x == 0.0));
+    }
+
+    #[test]
+    fn multiply_is_associative(
+        (a, b, c) in (1..8usize, 1..8usize, 1..8usize, 1..8usize).prop_flat_map(|(m, k, l, n)| {
+            (any_matrix(m, k), any_matrix(k, l), any_matrix(l, n))
+        })
+    ) {
+        assert_close(&((&a * &b) * &c), &(&a * &(&b * &c)), 1e-9);
+    }
+
+    #[test]
+    fn transpose_of_product(
+        (a, b) in (1..8usize, 1..8usize, 1..8usize).prop_flat_map(|(m, k, n)| {
+            (any_matrix(m, k), any_matrix(k, n))
+        })
+    ) {
+        assert_close(&(&a * &b).transpose(), &(b.transpose() * &a.transpose()), 1e-12);
+        assert_close(&a.matmul_tn(&a), &(a.transpose() * &a), 1e-12);
+        assert_close(&b.matmul_nt(&b), &(&b * &b.transpose()), 1e-12);
+    }
+}
+
+proptest! {
+    // Large enough to take the blocked, and for f32 the SIMD, path rather than the small loop
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn blocked_multiply_matches_reference(
+        (a, b) in (20..90usize, 20..300usize, 20..90usize).prop_flat_map(|(m, k, n)| {
+            (
+                prop::collection::vec(-1.0..1.0f32, m * k).prop_map(move |data| matrix(m, k, data)),
+                prop::collection::vec(-1.0..1.0f32, k * n).prop_map(move |data| matrix(k, n, data)),
+            )
+        })
+    ) {
+        let expected = naive_product(&a, &b);
+        let product = &a * &b;
+
+        prop_assert_eq!((product.rows, product.cols), (expected.rows, expected.cols));
+        for (x, y) in product.data.iter().zip(&expected.data) {
+            prop_assert!((x - y).abs() <= 1e-4 * a.cols as f32, "{} != {}", x, y);
+        }
+        assert_close(&a.matmul_nt(&b.transpose()).cast(), &expected.cast(), 1e-4);
+    }
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use neural_network_ml::{
+    ActivationFunction, Network,
+    metrics::{CsvSink, EpochRecord, JsonLinesSink, MetricSink, MetricsLogger},
+    training::{CheckpointInterval, Checkpointing, TrainingConfig},
+};
+
+use common::TemporaryFile;
+
+fn record(val_loss: Option<f32>) -> EpochRecord {
+    EpochRecord {
+        epoch: 2,
+        step: 20,
+        train_loss: 0.5,
+        val_loss,
+        accuracy: 0.75,
+        val_accuracy: val_loss.map(|_| 0.5),
+        learning_rate: 0.1,
+        wall_time: 1.5,
+        samples_per_second: 100.0,
+    }
+}
+
+#[test]
+fn csv_writes_a_header_then_rows() {
+    let mut sink = CsvSink::new(Vec::new());
+    sink.record(&record(Some(0.25))).unwrap();
+    sink.record(&record(None)).unwrap();
+
+    let csv = String::from_utf8(sink.into_inner()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(
+        lines,
+        vec![
+            "epoch,step,train_loss,val_loss,accuracy,val_accuracy,learning_rate,wall_time,samples_per_second",
+            "2,20,0.5,0.25,0.75,0.5,0.1,1.5,100",
+            "2,20,0.5,,0.75,,0.1,1.5,100",
+        ]
+    );
+}
+
+#[test]
+fn json_lines_round_trip() {
+    let mut sink = JsonLinesSink::new(Vec::new());
+    sink.record(&record(Some(0.25))).unwrap();
+    sink.record(&record(None)).unwrap();
+
+    let json = String::from_utf8(sink.into_inner()).unwrap();
+    let records: Vec<EpochRecord> = json
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(records, vec![record(Some(0.25)), record(None)]);
+}
+
+#[test]
+fn logger_records_every_epoch() {
+    let mut network: Network<f64> = Network::builder(2)
+        .layer(3, ActivationFunction::Tanh)
+        .layer(2, ActivationFunction::Sigmoid)
+        .seed(1)
+        .build();
+    let inputs = vec![vec![0.0, 1.0]; 10];
+    let targets = vec![vec![1.0, 0.0]; 10];
+    let config = TrainingConfig {
+        epochs: 3,
+        mini_batch_size: 4,
+        validation_split: 0.2,
+        ..TrainingConfig::default()
+    };
+
+    let mut logger = MetricsLogger::new(JsonLinesSink::new(Vec::new()));
+    let stats = network.stochastic_train_with(&inputs, &targets, &config, &mut [&mut logger]);
+    assert!(logger.error().is_none());
+
+    let json = String::from_utf8(logger.into_sink().into_inner()).unwrap();
+    let records: Vec<EpochRecord> = json
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    // 8 training samples in batches of 4
+    assert_eq!(records.len(), 3);
+    for (i, (record, stats)) in records.iter().zip(&stats).enumerate() {
+        assert_eq!(record.epoch, stats.epoch);
+        assert_eq!(record.step, 2 * (i as u64 + 1));
+        assert_eq!(record.train_loss, stats.loss);
+        assert_eq!(record.val_loss, stats.validation_loss);
+        assert!(record.wall_time > 0.0);
+    }
+}
+
+#[test]
+fn logger_counts_steps_and_time_from_before_a_resume() {
+    let network = || -> Network<f64> {
+        Network::builder(2)
+            .layer(3, ActivationFunction::Tanh)
+            .layer(2, ActivationFunction::Sigmoid)
+            .seed(1)
+            .build()
+    };
+    let inputs = vec![vec![0.0, 1.0]; 8];
+    let targets = vec![vec![1.0, 0.0]; 8];
+    let file = TemporaryFile::new("metrics-checkpoint.mpk");
+    let path = file.path().to_string();
+    let config = |epochs| TrainingConfig {
+        epochs,
+        mini_batch_size: 4,
+        ..TrainingConfig::default()
+    };
+
+    let first = network().stochastic_train(
+        &inputs,
+        &targets,
+        &TrainingConfig {
+            checkpoint: Some(Checkpointing {
+                path: path.clone(),
+                every: CheckpointInterval::Epochs(1),
+            }),
+            ..config(2)
+        },
+    );
+
+    let mut logger = MetricsLogger::new(JsonLinesSink::new(Vec::new()));
+    network().stochastic_train_with(
+        &inputs,
+        &targets,
+        &TrainingConfig {
+            resume_from: Some(path.clone()),
+            ..config(4)
+        },
+        &mut [&mut logger],
+    );
+
+    let json = String::from_utf8(logger.into_sink().into_inner()).unwrap();
+    let records: Vec<EpochRecord> = json
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    // Two mini batches an epoch, carrying on from the end of the second epoch
+    let steps: Vec<(u16, u64)> = records
+        .iter()
+        .map(|record| (record.epoch, record.step))
+        .collect();
+    assert_eq!(steps, vec![(3, 6), (4, 8)]);
+    assert!(records[0].wall_time >= first[1].elapsed.as_secs_f64());
+    assert!(records[1].wall_time >= records[0].wall_time);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use std::fs;
+
+use neural_network_ml::{
+    Error,
+    datasets::mnist::{unpack, unpack_images},
+};
+
+use common::TemporaryFile;
+
+fn images_file(count: u32, rows: u32, cols: u32, pixels: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for value in [0x00000803, count, rows, cols] {
+        bytes.extend(u32::to_be_bytes(value));
+    }
+    bytes.extend(pixels);
+    bytes
+}
+
+fn labels_file(count: u32, labels: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for value in [0x00000801, count] {
+        bytes.extend(u32::to_be_bytes(value));
+    }
+    bytes.extend(labels);
+    bytes
+}
+
+fn assert_length_mismatch<T>(result: Result<T, Error>, expected: usize, found: usize) {
+    match result {
+        Err(Error::LengthMismatch {
+            expected: e,
+            found: f,
+        }) => assert_eq!((e, f), (expected, found)),
+        Err(error) => panic!("Expected a length mismatch, found {error}"),
+        Ok(_) => panic!("Expected a length mismatch"),
+    }
+}
+
+#[test]
+fn reads_the_image_dimensions_from_the_header() {
+    let (images, labels) = (
+        TemporaryFile::new("dims-images"),
+        TemporaryFile::new("dims-labels"),
+    );
+    let (images, labels) = (images.path(), labels.path());
+    fs::write(
+        images,
+        images_file(2, 2, 3, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]),
+    )
+    .unwrap();
+    fs::write(labels, labels_file(2, &[4, 7])).unwrap();
+
+    let data = unpack(images, labels);
+    let unlabelled = unpack_images(images);
+
+    let data = data.unwrap();
+    assert_eq!(
+        data.images,
+        vec![vec![1, 2, 3, 4, 5, 6], vec![7, 8, 9, 10, 11, 12]]
+    );
+    assert_eq!(data.labels, vec![4, 7]);
+    assert_eq!(unlabelled.unwrap(), data.images);
+}
+
+#[test]
+fn counts_past_the_end_of_the_file_are_errors() {
+    let (images, labels) = (
+        TemporaryFile::new("huge-images"),
+        TemporaryFile::new("huge-labels"),
+    );
+    let (images, labels) = (images.path(), labels.path());
+    // Claims 2^32 - 1 images of 28x28 with only one in the file
+    fs::write(images, images_file(u32::MAX, 28, 28, &[0; 784])).unwrap();
+    fs::write(labels, labels_file(3, &[1])).unwrap();
+
+    let huge = unpack_images(images);
+    fs::write(images, images_file(3, 28, 28, &[0; 3 * 784])).unwrap();
+    let truncated_labels = unpack(images, labels);
+
+    assert_length_mismatch(huge, u32::MAX as usize * 784, 784);
+    assert_length_mismatch(truncated_labels, 3, 1);
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use std::fs;
+
+use neural_network_ml::{
+    ActivationFunction, ElementType, Error, Loss, Matrix, Network, network::element_type_of_file,
+    optimizer::OptimizerKind, training::TrainingConfig,
+};
+
+use common::{TemporaryFile, network, samples};
+
+fn parameters(network: &Network<f64>) -> Vec<Vec<f64>> {
+    network
+        .weights()
+        .iter()
+        .chain(network.biases())
+        .map(|matrix| matrix.data.clone())
+        .collect()
+}
+
+#[test]
+fn same_seed_gives_identical_weights_and_training() {
+    assert_eq!(parameters(&network(7)), parameters(&network(7)));
+    assert_ne!(parameters(&network(7)), parameters(&network(8)));
+
+    let (inputs, targets) = samples(20);
+    let config = TrainingConfig {
+        epochs: 3,
+        mini_batch_size: 3,
+        ..TrainingConfig::default()
+    };
+    let (mut first, mut second) = (network(7), network(7));
+    let first_stats = first.stochastic_train(&inputs, &targets, &config);
+    let second_stats = second.stochastic_train(&inputs, &targets, &config);
+
+    assert_eq!(parameters(&first), parameters(&second));
+    for (first, second) in first_stats.iter().zip(&second_stats) {
+        assert_eq!(first.loss, second.loss);
+    }
+}
+
+#[test]
+fn recorded_seed_rebuilds_an_unseeded_network() {
+    let unseeded: Network<f64> = Network::builder(2)
+        .layer(4, ActivationFunction::Tanh)
+        .layer(2, ActivationFunction::Sigmoid)
+        .learning_rate(0.5)
+        .build();
+
+    assert_eq!(parameters(&network(unseeded.seed())), parameters(&unseeded));
+}
+
+#[test]
+fn seed_is_saved_with_the_network() {
+    let file = TemporaryFile::new("seed.mpk");
+    let network = network(42);
+
+    network.save(file.path()).unwrap();
+    let loaded: Network<f64> = Network::from_file(file.path()).unwrap();
+
+    assert_eq!(loaded.seed(), 42);
+    assert_eq!(parameters(&loaded), parameters(&network));
+}
+
+#[test]
+fn saved_networks_record_their_element_type() {
+    let file = TemporaryFile::new("element-type.mpk");
+    let network = network(5);
+
+    network.save(file.path()).unwrap();
+    let element_type = element_type_of_file(file.path());
+    let loaded: Result<Network<f64>, Error> = Network::from_file(file.path());
+    let mismatched: Result<Network<f32>, Error> = Network::from_file(file.path());
+
+    assert_eq!(element_type.unwrap(), ElementType::F64);
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded.element_type(), ElementType::F64);
+    assert_eq!(loaded.layers(), network.layers());
+    assert_eq!(loaded.activations(), network.activations());
+    assert_eq!(parameters(&loaded), parameters(&network));
+    match mismatched {
+        Err(Error::ElementTypeMismatch { expected, found }) => {
+            assert_eq!((expected, found), (ElementType::F32, ElementType::F64))
+        }
+        Err(error) => panic!("Expected an element type mismatch, found {error}"),
+        Ok(_) => panic!("Expected an element type mismatch"),
+    }
+}
+
+// The fields of a saved network in order, so a test can tamper with them
+type Fields = (
+    ElementType,
+    Vec<usize>,
+    Vec<Matrix<f64>>,
+    Vec<Matrix<f64>>,
+    Vec<ActivationFunction>,
+    Loss,
+    f32,
+    OptimizerKind<f64>,
+    u64,
+);
+
+fn load_tampered(tamper: impl FnOnce(&mut Fields)) -> Result<Network<f64>, Error> {
+    let file = TemporaryFile::new("tampered.mpk");
+    network(9).save(file.path()).unwrap();
+
+    let mut fields: Fields = rmp_serde::from_slice(&fs::read(file.path()).unwrap()).unwrap();
+    tamper(&mut fields);
+    fs::write(file.path(), rmp_serde::to_vec(&fields).unwrap()).unwrap();
+
+    Network::from_file(file.path())
+}
+
+#[test]
+fn loading_a_network_checks_its_structure() {
+    let truncated = load_tampered(|fields| {
+        fields.2[0].data.pop();
+    });
+    let transposed = load_tampered(|fields| {
+        let weights = &mut fields.2[1];
+        (weights.rows, weights.cols) = (weights.cols, weights.rows);
+    });
+    let wide_bias = load_tampered(|fields| fields.3[0] = Matrix::zeros(4, 2));
+    let missing_activation = load_tampered(|fields| {
+        fields.4.pop();
+    });
+
+    for result in [truncated, transposed, wide_bias, missing_activation] {
+        match result {
+            Err(Error::CorruptNetwork(_)) => {}
+            Err(error) => panic!("Expected a corrupt network, found {error}"),
+            Ok(_) => panic!("Expected a corrupt network"),
+        }
+    }
+}
+
+#[test]
+fn mismatched_gradients_and_samples_are_errors() {
+    let mut network = network(4);
+    let weights = network.weights().to_vec();
+    let biases = network.biases().to_vec();
+
+    assert!(matches!(
+        network.try_update_network(&weights[..1], &biases),
+        Err(Error::LengthMismatch {
+            expected: 2,
+            found: 1
+        })
+    ));
+    assert!(matches!(
+        network.try_update_network(&[weights[0].clone(), weights[0].clone()], &biases),
+        Err(Error::ShapeMismatch { .. })
+    ));
+    assert!(matches!(
+        network.try_loss(&vec![0.0; 3], &vec![1.0, 0.0]),
+        Err(Error::LengthMismatch {
+            expected: 2,
+            found: 3
+        })
+    ));
+    assert!(network.try_update_network(&weights, &biases).is_ok());
+}
@@ -0,0 +1,188 @@
+use neural_network_ml::{
+    Matrix,
+    optimizer::{Adagrad, Adam, AdamW, Nesterov, Optimizer, RmsProp, Sgd},
+};
+
+fn assert_close(found: f64, expected: f64) {
+    assert!(
+        (found - expected).abs() < 1e-6,
+        "expected {expected}, found {found}"
+    );
+}
+
+// Value of a single parameter starting at 1 after one update per gradient
+fn steps(optimizer: &mut impl Optimizer<f64>, gradients: &[f64], learning_rate: f32) -> Vec<f64> {
+    let mut parameter = Matrix::from(vec![1.0]);
+
+    gradients
+        .iter()
+        .map(|gradient| {
+            optimizer.begin_step();
+            optimizer.step(
+                0,
+                &mut parameter,
+                &Matrix::from(vec![*gradient]),
+                learning_rate,
+            );
+            parameter.data[0]
+        })
+        .collect()
+}
+
+fn assert_steps(optimizer: &mut impl Optimizer<f64>, gradients: &[f64], expected: &[f64]) {
+    let found = steps(optimizer, gradients, 0.1);
+
+    for (found, expected) in found.into_iter().zip(expected) {
+        assert_close(found, *expected);
+    }
+}
+
+#[test]
+fn sgd_without_momentum() {
+    assert_steps(&mut Sgd::new(0.0), &[0.5, 0.5], &[0.95, 0.9]);
+}
+
+#[test]
+fn sgd_with_momentum() {
+    // v = 0.5 then 0.9 * 0.5 + 0.5
+    assert_steps(&mut Sgd::new(0.9), &[0.5, 0.5], &[0.95, 0.855]);
+}
+
+#[test]
+fn nesterov_looks_ahead() {
+    // p -= lr * (g + momentum * v), with v = 0.5 then 0.95
+    assert_steps(&mut Nesterov::new(0.9), &[0.5, 0.5], &[0.905, 0.7695]);
+}
+
+#[test]
+fn adam_corrects_bias() {
+    // The first step has m_hat = g and v_hat = g^2 so moves by the learning rate, the second has
+    // m_hat = -0.005 / 0.19 and v_hat = 0.25
+    assert_steps(
+        &mut Adam::default(),
+        &[0.5, -0.5],
+        &[0.9, 0.9 + 0.1 * 0.005 / 0.19 / 0.5],
+    );
+}
+
+#[test]
+fn adamw_decays_before_the_adam_step() {
+    assert_steps(
+        &mut AdamW::default(),
+        &[0.5],
+        &[1.0 * (1.0 - 0.1 * 0.01) - 0.1],
+    );
+}
+
+#[test]
+fn adamw_decays_without_a_gradient() {
+    let decay: f64 = 1.0 - 0.1 * 0.01;
+    let found = steps(&mut AdamW::default(), &[0.0, 0.0], 0.1);
+
+    assert_close(found[0], decay);
+    assert_close(found[1], decay * decay);
+}
+
+#[test]
+fn rmsprop_divides_by_running_mean_square() {
+    // s = 0.025 then 0.0475
+    assert_steps(
+        &mut RmsProp::default(),
+        &[0.5, 0.5],
+        &[
+            1.0 - 0.05 / 0.025f64.sqrt(),
+            1.0 - 0.05 / 0.025f64.sqrt() - 0.05 / 0.0475f64.sqrt(),
+        ],
+    );
+}
+
+#[test]
+fn adagrad_divides_by_summed_squares() {
+    // s = 0.25 then 0.5
+    assert_steps(
+        &mut Adagrad::default(),
+        &[0.5, 0.5],
+        &[0.9, 0.9 - 0.05 / 0.5f64.sqrt()],
+    );
+}
+
+#[test]
+fn state_is_kept_per_slot() {
+    let mut optimizer: Sgd<f64> = Sgd::new(0.9);
+    let mut first = Matrix::from(vec![1.0]);
+    let mut second = Matrix::from(vec![1.0]);
+    let gradient = Matrix::from(vec![0.5]);
+
+    optimizer.step(0, &mut first, &gradient, 0.1);
+    optimizer.step(0, &mut first, &gradient, 0.1);
+    optimizer.step(1, &mut second, &gradient, 0.1);
+
+    assert_close(first.data[0], 0.855);
+    assert_close(second.data[0], 0.95);
+}
+
+#[cfg(feature = "half")]
+mod half_precision {
+    use half::f16;
+
+    use neural_network_ml::{
+        ActivationFunction, Matrix, Network,
+        optimizer::{Adagrad, Adam, AdamW, Optimizer, OptimizerKind, RmsProp},
+        training::TrainingConfig,
+    };
+
+    // A zero gradient has to leave its parameter finite, the default epsilons underflow in f16
+    fn assert_zero_gradient_is_finite(mut optimizer: impl Optimizer<f16>) {
+        let mut parameter = Matrix::from(vec![f16::ONE, f16::ONE]);
+        let gradient = Matrix::from(vec![f16::ZERO, f16::from_f32(0.5)]);
+
+        for _ in 0..3 {
+            optimizer.begin_step();
+            optimizer.step(0, &mut parameter, &gradient, 0.01);
+        }
+
+        assert!(parameter.data.iter().all(|value| value.is_finite()));
+        assert!(parameter.data[1] < parameter.data[0]);
+    }
+
+    #[test]
+    fn adaptive_optimizers_handle_zero_gradients() {
+        assert_zero_gradient_is_finite(Adam::default());
+        assert_zero_gradient_is_finite(AdamW::default());
+        assert_zero_gradient_is_finite(RmsProp::default());
+        assert_zero_gradient_is_finite(Adagrad::default());
+    }
+
+    #[test]
+    fn adam_trains_with_a_constant_zero_feature() {
+        let mut network: Network<f16> = Network::builder(2)
+            .layer(4, ActivationFunction::Tanh)
+            .layer(2, ActivationFunction::Sigmoid)
+            .optimizer(OptimizerKind::Adam(Adam::default()))
+            .learning_rate(0.01)
+            .seed(1)
+            .build();
+        let inputs: Vec<Vec<f16>> = (0..8)
+            .map(|i| vec![f16::ZERO, f16::from_f32(i as f32 / 8.0)])
+            .collect();
+        let targets: Vec<Vec<f16>> = (0..8)
+            .map(|i| {
+                if i % 2 == 0 {
+                    vec![f16::ONE, f16::ZERO]
+                } else {
+                    vec![f16::ZERO, f16::ONE]
+                }
+            })
+            .collect();
+        let config = TrainingConfig {
+            epochs: 2,
+            mini_batch_size: 4,
+            ..TrainingConfig::default()
+        };
+
+        network.stochastic_train(&inputs, &targets, &config);
+
+        let outputs = network.feed_forward(&inputs[0]);
+        assert!(outputs.iter().all(|value| value.is_finite()));
+    }
+}
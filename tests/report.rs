@@ -0,0 +1,146 @@
+use neural_network_ml::{
+    ActivationFunction, Network, initializer::Initializer, report::EvaluationReport,
+};
+
+fn assert_close(found: f64, expected: f64) {
+    assert!(
+        (found - expected).abs() < 1e-12,
+        "expected {expected}, found {found}"
+    );
+}
+
+// Rows are labels and columns predictions
+fn report() -> EvaluationReport {
+    EvaluationReport::from_counts(vec![vec![3, 1, 0], vec![0, 2, 2], vec![0, 0, 0]], 2, 6, 4.0)
+}
+
+#[test]
+fn per_class_metrics() {
+    let report = report();
+    let class_0 = &report.classes[0];
+    let class_1 = &report.classes[1];
+
+    assert_eq!((class_0.support, class_1.support), (4, 4));
+    assert_close(class_0.precision, 1.0);
+    assert_close(class_0.recall, 0.75);
+    assert_close(class_0.f1, 6.0 / 7.0);
+    assert_close(class_1.precision, 2.0 / 3.0);
+    assert_close(class_1.recall, 0.5);
+    assert_close(class_1.f1, 4.0 / 7.0);
+
+    // Never predicted correctly and never labelled, every metric is 0 rather than NaN
+    assert_close(report.classes[2].f1, 0.0);
+}
+
+#[test]
+fn averages_and_totals() {
+    let report = report();
+
+    assert_eq!(report.samples, 8);
+    assert_close(report.accuracy, 5.0 / 8.0);
+    assert_close(report.top_k_accuracy, 6.0 / 8.0);
+    assert_close(report.loss, 0.5);
+    assert_close(report.macro_average.precision, (1.0 + 2.0 / 3.0) / 3.0);
+    assert_close(report.macro_average.recall, 1.25 / 3.0);
+    assert_close(report.micro_average.f1, report.accuracy);
+}
+
+#[test]
+fn csv_has_classes_metrics_and_confusion_matrix() {
+    let csv = report().to_csv();
+    let sections: Vec<Vec<&str>> = csv
+        .split("\n\n")
+        .map(|section| section.lines().collect())
+        .collect();
+
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[0][0], "class,precision,recall,f1,support");
+    assert_eq!(sections[0].len(), 6);
+    assert!(sections[0][4].starts_with("macro,"));
+    assert!(sections[0][5].starts_with("micro,"));
+    assert_eq!(
+        sections[1],
+        vec![
+            "metric,value",
+            "samples,8",
+            "loss,0.5",
+            "accuracy,0.625",
+            "top_k,2",
+            "top_k_accuracy,0.75"
+        ]
+    );
+    assert_eq!(
+        sections[2],
+        vec![
+            "label,predicted_0,predicted_1,predicted_2",
+            "0,3,1,0",
+            "1,0,2,2",
+            "2,0,0,0"
+        ]
+    );
+}
+
+// Outputs all tie at zero, so every sample is predicted as the first class. Ties rank the lower
+// index first, so a label is within the top k when it is among the first k classes
+#[test]
+fn evaluate_with_tied_outputs() {
+    let network: Network<f64> = Network::builder(2)
+        .layer_with_initializer(3, ActivationFunction::Identity, Initializer::Zeros)
+        .build();
+    let inputs = vec![vec![1.0, 2.0]; 4];
+    let labels = vec![
+        vec![1.0, 0.0, 0.0],
+        vec![0.0, 1.0, 0.0],
+        vec![0.0, 1.0, 0.0],
+        vec![0.0, 0.0, 1.0],
+    ];
+
+    let report = network.evaluate(&inputs, &labels, 1).unwrap();
+    let top_2 = network.evaluate(&inputs, &labels, 2).unwrap();
+
+    assert_eq!(
+        report.confusion_matrix,
+        vec![vec![1, 0, 0], vec![2, 0, 0], vec![1, 0, 0]]
+    );
+    assert_close(report.accuracy, 0.25);
+    assert_close(report.top_k_accuracy, report.accuracy);
+    assert_close(top_2.top_k_accuracy, 0.75);
+    assert_close(report.loss, network.loss(&inputs[0], &labels[0]));
+}
+
+// Checks the confusion matrix and top-k hits against ranks worked out from `feed_forward`
+#[test]
+fn evaluate_counts_predictions_and_ranks() {
+    let network: Network<f64> = Network::builder(2)
+        .layer(4, ActivationFunction::Tanh)
+        .layer(4, ActivationFunction::Softmax)
+        .seed(11)
+        .build();
+    let inputs: Vec<Vec<f64>> = (0..40)
+        .map(|i| vec![(i % 7) as f64 - 3.0, (i % 5) as f64 - 2.0])
+        .collect();
+    let labels: Vec<Vec<f64>> = (0..40)
+        .map(|i| (0..4).map(|c| if i % 4 == c { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    let mut confusion_matrix = vec![vec![0; 4]; 4];
+    let mut top_2_hits = 0;
+    for (i, input) in inputs.iter().enumerate() {
+        let outputs = network.feed_forward(input);
+        let mut order: Vec<usize> = (0..4).collect();
+        order.sort_by(|a, b| outputs[*b].total_cmp(&outputs[*a]));
+
+        confusion_matrix[i % 4][order[0]] += 1;
+        if order[..2].contains(&(i % 4)) {
+            top_2_hits += 1;
+        }
+    }
+
+    let report = network.evaluate(&inputs, &labels, 2).unwrap();
+
+    assert_eq!(report.confusion_matrix, confusion_matrix);
+    assert_close(report.top_k_accuracy, top_2_hits as f64 / 40.0);
+    // Distinct predictions, so the counts are not all on the diagonal or in one column
+    assert!(report.accuracy > 0.0 && report.accuracy < 1.0);
+    assert!(report.top_k_accuracy > report.accuracy);
+}
@@ -0,0 +1,124 @@
+use neural_network_ml::schedule::{LrSchedule, Scheduler};
+
+const STEPS_PER_EPOCH: u64 = 4;
+
+fn assert_close(found: f32, expected: f32) {
+    assert!(
+        (found - expected).abs() < 1e-6,
+        "expected {expected}, found {found}"
+    );
+}
+
+// Learning rate of the first mini batch of every epoch, feeding `losses` to the end of each epoch
+fn epoch_rates(schedule: LrSchedule, losses: &[f32]) -> Vec<f32> {
+    let mut scheduler = Scheduler::new(schedule, 1.0, STEPS_PER_EPOCH * losses.len() as u64);
+    let mut rates = Vec::new();
+
+    for loss in losses {
+        rates.push(scheduler.learning_rate());
+        for _ in 0..STEPS_PER_EPOCH {
+            scheduler.step();
+        }
+        scheduler.end_epoch(*loss);
+    }
+
+    rates
+}
+
+// Learning rate of every mini batch over `steps` of them
+fn step_rates(schedule: LrSchedule, steps: u64) -> Vec<f32> {
+    let mut scheduler = Scheduler::new(schedule, 1.0, steps);
+
+    (0..steps)
+        .map(|_| {
+            let rate = scheduler.learning_rate();
+            scheduler.step();
+            rate
+        })
+        .collect()
+}
+
+#[test]
+fn step_and_exponential_decay() {
+    let rates = epoch_rates(
+        LrSchedule::StepDecay {
+            step_size: 2,
+            gamma: 0.5,
+        },
+        &[0.0; 5],
+    );
+    assert_eq!(rates, vec![1.0, 1.0, 0.5, 0.5, 0.25]);
+
+    let rates = epoch_rates(LrSchedule::Exponential { gamma: 0.5 }, &[0.0; 3]);
+    assert_eq!(rates, vec![1.0, 0.5, 0.25]);
+}
+
+#[test]
+fn cosine_annealing_restarts_with_longer_periods() {
+    let rates = epoch_rates(
+        LrSchedule::CosineAnnealing {
+            period: 2,
+            period_multiplier: 2,
+            min_lr: 0.0,
+        },
+        &[0.0; 7],
+    );
+
+    for (found, expected) in
+        rates
+            .into_iter()
+            .zip([1.0, 0.5, 1.0, 0.853_553_4, 0.5, 0.146_446_6, 1.0])
+    {
+        assert_close(found, expected);
+    }
+}
+
+#[test]
+fn warmup_ramps_up_to_the_wrapped_schedule() {
+    let rates = step_rates(
+        LrSchedule::Warmup {
+            steps: 4,
+            then: Box::new(LrSchedule::Constant),
+        },
+        6,
+    );
+
+    assert_eq!(rates, vec![0.25, 0.5, 0.75, 1.0, 1.0, 1.0]);
+}
+
+#[test]
+fn one_cycle_peaks_then_anneals() {
+    let rates = step_rates(
+        LrSchedule::OneCycle {
+            max_lr: 1.0,
+            warmup_fraction: 0.25,
+            div_factor: 10.0,
+            final_div_factor: 100.0,
+        },
+        9,
+    );
+
+    assert_close(rates[0], 0.1);
+    assert_close(rates[2], 1.0);
+    assert!(rates.windows(2).take(2).all(|pair| pair[0] < pair[1]));
+    assert!(rates.windows(2).skip(2).all(|pair| pair[0] > pair[1]));
+    assert_close(rates[8], 0.001);
+}
+
+#[test]
+fn reduce_on_plateau_waits_for_patience() {
+    let rates = epoch_rates(
+        LrSchedule::ReduceOnPlateau {
+            factor: 0.5,
+            patience: 2,
+            min_delta: 0.0,
+            min_lr: 0.2,
+        },
+        &[1.0, 0.9, 0.9, 0.95, 0.8, 0.8, 0.8, 0.8, 0.8, 0.8, 0.8],
+    );
+
+    assert_eq!(
+        rates,
+        vec![1.0, 1.0, 1.0, 1.0, 0.5, 0.5, 0.5, 0.25, 0.25, 0.2, 0.2]
+    );
+}
@@ -0,0 +1,406 @@
+mod common;
+
+use neural_network_ml::{
+    ActivationFunction, Error, Network,
+    callback::{BatchStats, Callback, Control},
+    optimizer::{Adam, OptimizerKind},
+    schedule::LrSchedule,
+    training::{
+        CheckpointInterval, Checkpointing, EarlyStopping, EpochStats, Evaluation, Monitor,
+        TrainingConfig,
+    },
+};
+
+use common::{TemporaryFile, network, samples};
+
+fn config(epochs: u16, early_stopping: Option<EarlyStopping>) -> TrainingConfig {
+    TrainingConfig {
+        epochs,
+        mini_batch_size: 4,
+        validation_split: 0.2,
+        early_stopping,
+        ..TrainingConfig::default()
+    }
+}
+
+#[test]
+fn validation_metrics_are_reported() {
+    let (inputs, targets) = samples(40);
+    let stats = network(3).stochastic_train(&inputs, &targets, &config(3, None));
+
+    assert_eq!(stats.len(), 3);
+    assert!(stats.iter().all(|epoch| epoch.validation_loss.is_some()));
+    assert!(
+        stats
+            .iter()
+            .all(|epoch| epoch.validation_accuracy.is_some())
+    );
+}
+
+#[test]
+fn early_stopping_restores_the_best_weights() {
+    let (inputs, targets) = samples(40);
+    // Nothing beats the first epoch by this much
+    let early_stopping = EarlyStopping {
+        monitor: Monitor::ValidationLoss,
+        patience: 2,
+        min_delta: 1e9,
+        restore_best_weights: true,
+    };
+
+    let mut stopped = network(3);
+    let stats = stopped.stochastic_train(&inputs, &targets, &config(50, Some(early_stopping)));
+    let mut one_epoch = network(3);
+    one_epoch.stochastic_train(&inputs, &targets, &config(1, None));
+
+    assert_eq!(stats.len(), 3);
+    for input in &inputs[..4] {
+        assert_eq!(stopped.feed_forward(input), one_epoch.feed_forward(input));
+    }
+}
+
+fn assert_invalid_config(result: Result<Vec<EpochStats>, Error>, reason: &str) {
+    match result {
+        Err(Error::InvalidConfig(found)) => assert!(
+            found.starts_with(reason),
+            "expected {reason:?}, found {found:?}"
+        ),
+        Err(error) => panic!("Expected an invalid config, found {error}"),
+        Ok(_) => panic!("Expected an invalid config"),
+    }
+}
+
+#[test]
+fn invalid_configs_are_errors() {
+    let (inputs, targets) = samples(40);
+    let train =
+        |config: TrainingConfig| network(3).try_stochastic_train(&inputs, &targets, &config);
+
+    assert_invalid_config(
+        train(TrainingConfig {
+            mini_batch_size: 0,
+            ..TrainingConfig::default()
+        }),
+        "Mini batch size must be positive",
+    );
+    for validation_split in [1.0, 1.5, -0.1] {
+        assert_invalid_config(
+            train(TrainingConfig {
+                validation_split,
+                ..TrainingConfig::default()
+            }),
+            "Validation split must be in [0, 1)",
+        );
+    }
+    assert_invalid_config(
+        train(TrainingConfig {
+            early_stopping: Some(EarlyStopping::default()),
+            ..TrainingConfig::default()
+        }),
+        "Early stopping needs a validation split",
+    );
+    // Rounds to no validation samples out of 40
+    assert_invalid_config(
+        train(TrainingConfig {
+            validation_split: 0.01,
+            early_stopping: Some(EarlyStopping::default()),
+            ..TrainingConfig::default()
+        }),
+        "Early stopping needs a validation split",
+    );
+    assert_invalid_config(
+        train(TrainingConfig {
+            mini_batch_size: 50,
+            drop_last: true,
+            ..TrainingConfig::default()
+        }),
+        "Dropping the last mini batch leaves nothing to train on",
+    );
+}
+
+#[test]
+fn a_split_holding_out_every_sample_is_an_error() {
+    let (inputs, targets) = samples(40);
+    let config = TrainingConfig {
+        validation_split: 0.6,
+        ..TrainingConfig::default()
+    };
+
+    assert_invalid_config(
+        network(3).try_stochastic_train(&inputs[..1], &targets[..1], &config),
+        "No samples are left to train on",
+    );
+}
+
+#[test]
+fn resuming_from_a_checkpoint_matches_an_uninterrupted_run() {
+    let (inputs, targets) = samples(40);
+    let file = TemporaryFile::new("checkpoint.mpk");
+    let path = file.path().to_string();
+    let adam = || {
+        Network::builder(2)
+            .layer(4, ActivationFunction::Tanh)
+            .layer(2, ActivationFunction::Sigmoid)
+            .optimizer(OptimizerKind::Adam(Adam::default()))
+            .learning_rate(0.05)
+            .seed(3)
+            .build()
+    };
+    // 32 training samples in batches of 5 is 7 mini batches an epoch, the last of 2 samples
+    let config = |epochs| TrainingConfig {
+        epochs,
+        mini_batch_size: 5,
+        validation_split: 0.2,
+        lr_schedule: LrSchedule::ReduceOnPlateau {
+            factor: 0.5,
+            patience: 1,
+            min_delta: 0.0,
+            min_lr: 0.0,
+        },
+        ..TrainingConfig::default()
+    };
+
+    let mut uninterrupted: Network<f64> = adam();
+    let expected = uninterrupted.stochastic_train(&inputs, &targets, &config(4));
+
+    // Stops after 14 mini batches, the last checkpoint is from the middle of the second epoch
+    let mut interrupted: Network<f64> = adam();
+    interrupted.stochastic_train(
+        &inputs,
+        &targets,
+        &TrainingConfig {
+            checkpoint: Some(Checkpointing {
+                path: path.clone(),
+                every: CheckpointInterval::Steps(4),
+            }),
+            ..config(2)
+        },
+    );
+
+    let mut resumed: Network<f64> = adam();
+    let stats = resumed.stochastic_train(
+        &inputs,
+        &targets,
+        &TrainingConfig {
+            resume_from: Some(path.clone()),
+            ..config(4)
+        },
+    );
+
+    assert_eq!(stats.len(), 4);
+    for (found, expected) in stats.iter().zip(&expected) {
+        assert_eq!(found.loss, expected.loss);
+        assert_eq!(found.validation_loss, expected.validation_loss);
+        assert_eq!(found.learning_rate, expected.learning_rate);
+    }
+    for input in &inputs[..4] {
+        assert_eq!(
+            resumed.feed_forward(input),
+            uninterrupted.feed_forward(input)
+        );
+    }
+}
+
+#[test]
+fn resuming_a_different_architecture_is_an_error() {
+    let (inputs, targets) = samples(40);
+    let file = TemporaryFile::new("checkpoint-3-inputs.mpk");
+    let path = file.path().to_string();
+    let three_inputs = || -> Network<f64> {
+        Network::builder(3)
+            .layer(4, ActivationFunction::Tanh)
+            .layer(2, ActivationFunction::Sigmoid)
+            .seed(3)
+            .build()
+    };
+    let wide_inputs: Vec<Vec<f64>> = inputs.iter().map(|x| vec![x[0], x[1], 0.0]).collect();
+    three_inputs().stochastic_train(
+        &wide_inputs,
+        &targets,
+        &TrainingConfig {
+            checkpoint: Some(Checkpointing {
+                path: path.clone(),
+                every: CheckpointInterval::Epochs(1),
+            }),
+            ..config(1, None)
+        },
+    );
+
+    let resume = TrainingConfig {
+        resume_from: Some(path.clone()),
+        ..config(2, None)
+    };
+    let into_two_inputs = network(3).try_stochastic_train(&inputs, &targets, &resume);
+    let on_two_inputs = three_inputs().try_stochastic_train(&inputs, &targets, &resume);
+
+    match into_two_inputs {
+        Err(Error::ArchitectureMismatch { expected, found }) => {
+            assert_eq!((expected, found), (vec![2, 4, 2], vec![3, 4, 2]))
+        }
+        Err(error) => panic!("Expected an architecture mismatch, found {error}"),
+        Ok(_) => panic!("Expected an architecture mismatch"),
+    }
+    match on_two_inputs {
+        Err(Error::LengthMismatch { expected, found }) => assert_eq!((expected, found), (3, 2)),
+        Err(error) => panic!("Expected a length mismatch, found {error}"),
+        Ok(_) => panic!("Expected a length mismatch"),
+    }
+}
+
+#[test]
+fn resuming_with_other_settings_is_an_error() {
+    let (inputs, targets) = samples(40);
+    let file = TemporaryFile::new("checkpoint-settings.mpk");
+    let path = file.path().to_string();
+    network(3).stochastic_train(
+        &inputs,
+        &targets,
+        &TrainingConfig {
+            checkpoint: Some(Checkpointing {
+                path: path.clone(),
+                every: CheckpointInterval::Epochs(1),
+            }),
+            ..config(1, None)
+        },
+    );
+    let resume = |config: TrainingConfig| {
+        network(3).try_stochastic_train(
+            &inputs,
+            &targets,
+            &TrainingConfig {
+                resume_from: Some(path.clone()),
+                ..config
+            },
+        )
+    };
+
+    assert!(resume(config(3, None)).is_ok());
+    for (config, setting) in [
+        (
+            TrainingConfig {
+                mini_batch_size: 8,
+                ..config(3, None)
+            },
+            "a mini batch size of 4",
+        ),
+        (
+            TrainingConfig {
+                drop_last: true,
+                ..config(3, None)
+            },
+            "drop last false",
+        ),
+        (
+            TrainingConfig {
+                validation_split: 0.25,
+                ..config(3, None)
+            },
+            "a validation split of 0.2",
+        ),
+        (
+            TrainingConfig {
+                lr_schedule: LrSchedule::Exponential { gamma: 0.5 },
+                ..config(3, None)
+            },
+            "the learning rate schedule",
+        ),
+        (
+            TrainingConfig {
+                seed: Some(4),
+                ..config(3, None)
+            },
+            "seed 3",
+        ),
+    ] {
+        assert_invalid_config(
+            resume(config),
+            &format!("The checkpoint was trained with {setting}"),
+        );
+    }
+}
+
+// Counts every hook, stopping after `stop_after_batches` mini batches
+#[derive(Default)]
+struct Recorder {
+    epochs_started: Vec<u16>,
+    batches: usize,
+    epochs_ended: usize,
+    train_ended: bool,
+    stop_after_batches: Option<usize>,
+}
+
+impl Callback<f64> for Recorder {
+    fn on_epoch_start(&mut self, epoch: u16, _epochs: u16) -> Control {
+        self.epochs_started.push(epoch);
+        Control::Continue
+    }
+
+    fn on_batch_end(&mut self, _network: &Network<f64>, batch: &BatchStats) -> Control {
+        assert!(batch.loss.is_finite());
+        self.batches += 1;
+
+        if self.stop_after_batches == Some(self.batches) {
+            Control::Stop
+        } else {
+            Control::Continue
+        }
+    }
+
+    fn on_epoch_end(&mut self, _network: &Network<f64>, _stats: &EpochStats) -> Control {
+        self.epochs_ended += 1;
+        Control::Continue
+    }
+
+    fn on_train_end(&mut self, _network: &Network<f64>, _stats: &[EpochStats]) {
+        self.train_ended = true;
+    }
+}
+
+#[test]
+fn callbacks_see_every_hook() {
+    let (inputs, targets) = samples(40);
+    let mut recorder = Recorder::default();
+
+    network(3).stochastic_train_with(&inputs, &targets, &config(3, None), &mut [&mut recorder]);
+
+    // 32 training samples in batches of 4
+    assert_eq!(recorder.epochs_started, vec![1, 2, 3]);
+    assert_eq!(recorder.batches, 24);
+    assert_eq!(recorder.epochs_ended, 3);
+    assert!(recorder.train_ended);
+}
+
+#[test]
+fn callbacks_can_stop_training() {
+    let (inputs, targets) = samples(40);
+    let mut recorder = Recorder {
+        stop_after_batches: Some(10),
+        ..Recorder::default()
+    };
+
+    let stats =
+        network(3).stochastic_train_with(&inputs, &targets, &config(3, None), &mut [&mut recorder]);
+
+    // Stopped in the middle of the second epoch, which is left out
+    assert_eq!(stats.len(), 1);
+    assert_eq!(recorder.batches, 10);
+    assert!(recorder.train_ended);
+
+    let mut recorder = Recorder {
+        stop_after_batches: Some(50),
+        ..Recorder::default()
+    };
+    let stats = network(3)
+        .train(
+            &inputs,
+            &targets,
+            5,
+            Evaluation::Classification,
+            &mut [&mut recorder],
+        )
+        .unwrap();
+
+    // Per sample training runs 40 mini batches an epoch
+    assert_eq!(stats.len(), 1);
+    assert_eq!(recorder.epochs_started, vec![1, 2]);
+}